@@ -1,24 +1,36 @@
+use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 
-use bevy_internal::math::vec3;
+use bevy_internal::math::{ivec3, vec3, Vec3};
 use fastanvil::{Block, Chunk, CurrentJavaChunk, Region};
 use fastnbt::from_bytes;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
-use lib::entity::level::creature::{Creature, CreatureName};
+use lib::entity::archetype::CreatureArchetypes;
+use lib::entity::level::creature::Creature;
+use lib::entity::level::patrol::{attach_patrol_routes, patrol_mode};
+use lib::entity::level::validation::{validate, CollisionBox};
 use lib::entity::level::{DayPart, Level};
 use lib::entity::voxel::{Material, Shape, TrianglePrismProperties, Voxel};
-use mca_cuboids::{BlockCoordinates, ExportParams};
+use mca_cuboids::{BlockCoordinates, BlockSequence, ExportParams};
 
 const EXPORT_DIAPASON: usize = 8;
 const LVL_DIR: &str = "./assets/lvl/";
+const CREATURE_DIR: &str = "./assets/creature";
 const CHUNK_SIZE: usize = 16;
 const MAX_NEGATIVE_HEIGHT: f32 = 64.0;
+// Seconds a creature stands at every waypoint of a patrol route
+const WAYPOINT_WAIT: f32 = 1.0;
+const MARKER_COLORS: [&str; 16] = [
+    "white", "orange", "magenta", "light_blue", "yellow", "lime", "pink", "gray",
+    "light_gray", "cyan", "purple", "blue", "brown", "green", "red", "black",
+];
 
 fn main() {
     let lvls = fs::read_dir(LVL_DIR).expect("Cannot read files from lvls dir.");
+    let archetypes = CreatureArchetypes::from_dir(CREATURE_DIR);
 
     for dir in lvls.flatten() {
         let file_name = dir.file_name();
@@ -38,26 +50,31 @@ fn main() {
             if should_rebuild {
                 println!("Converting {original_lvl_path}");
 
-                {
-                    let mut collisions = mca_cuboids::export_cuboids(
-                        dir.path().to_str().unwrap(),
-                        ExportParams {
-                            start: BlockCoordinates::new(0, -64, 0),
-                            end: BlockCoordinates::new(64, 0, 64),
-                            skip_blocks: vec![
-                                "minecraft:flowering_azalea".to_owned(),
-                                "minecraft:grass".to_owned(),
-                                "minecraft:oxeye_daisy".to_owned(),
-                            ],
-                        },
-                    )
-                    .expect("Failed to build collisions");
-                    collisions.iter_mut().for_each(|seq| {
-                        seq.start.y += 64;
-                        seq.end.y += 64;
-                    });
-                    println!("collisions: {}", collisions.len());
+                let mut skip_blocks = vec![
+                    "minecraft:flowering_azalea".to_owned(),
+                    "minecraft:grass".to_owned(),
+                    "minecraft:oxeye_daisy".to_owned(),
+                ];
+                skip_blocks.extend(patrol_marker_names());
+                let mut collisions = mca_cuboids::export_cuboids(
+                    dir.path().to_str().unwrap(),
+                    ExportParams {
+                        start: BlockCoordinates::new(0, -64, 0),
+                        end: BlockCoordinates::new(64, 0, 64),
+                        skip_blocks,
+                    },
+                )
+                .expect("Failed to build collisions");
+                collisions.iter_mut().for_each(|seq| {
+                    seq.start.y += 64;
+                    seq.end.y += 64;
+                });
+                println!("collisions: {}", collisions.len());
+
+                let lvl = read_level(lvl_name, &archetypes);
+                validate_level(&lvl, &collisions);
 
+                {
                     let serialized_collisions =
                         serde_json::to_string(&collisions).expect("Cannot serialize collisions.");
                     let mut file = File::create(format!("{LVL_DIR}{}/collisions.json", lvl_name))
@@ -66,7 +83,6 @@ fn main() {
                         .expect("Cannot write collisions to file.");
                 }
                 {
-                    let lvl = read_level(lvl_name);
                     let serialized_lvl =
                         serde_json::to_string(&lvl).expect("Cannot serialize lvl.");
                     let file = File::create(converted_lvl_path)
@@ -83,9 +99,10 @@ fn main() {
     }
 }
 
-fn read_level(lvl_name: &str) -> Level {
+fn read_level(lvl_name: &str, archetypes: &CreatureArchetypes) -> Level {
     let mut voxels = vec![];
     let mut creatures = vec![];
+    let mut patrol_markers: HashMap<String, Vec<Vec3>> = HashMap::new();
     let path = [LVL_DIR, lvl_name, "/r.0.0.mca"].concat();
     let file = File::open(path).unwrap_or_else(|_| panic!("Can't open file {}", lvl_name));
 
@@ -110,16 +127,20 @@ fn read_level(lvl_name: &str) -> Level {
                             let voxel_z = (chunk_z * CHUNK_SIZE) + z;
                             let voxel_y = y as f32 + MAX_NEGATIVE_HEIGHT;
                             let point = vec3(voxel_x as f32, voxel_y, voxel_z as f32);
-                            match block.name() {
-                                "minecraft:oak_sign" => {
-                                    creatures.push(Creature::neytral(CreatureName::Dummy, point));
-                                    continue;
-                                }
-                                "minecraft:spruce_sign" => {
-                                    creatures.push(Creature::enemy(CreatureName::Pizza, point));
-                                    continue;
-                                }
-                                _ => {}
+                            if let Some(archetype) = archetypes.by_marker(block.name()) {
+                                creatures.push(Creature::new(
+                                    archetype.name.clone(),
+                                    archetype.relation,
+                                    point,
+                                ));
+                                continue;
+                            }
+                            if patrol_mode(block.name()).is_some() {
+                                patrol_markers
+                                    .entry(block.name().to_owned())
+                                    .or_default()
+                                    .push(point);
+                                continue;
                             }
 
                             let material = match_name_to_material(block.name());
@@ -135,6 +156,9 @@ fn read_level(lvl_name: &str) -> Level {
     });
 
     let day_part = DayPart::Night;
+    for issue in attach_patrol_routes(&mut creatures, patrol_markers, WAYPOINT_WAIT) {
+        println!("cargo:warning={issue}");
+    }
 
     // TODO sort voxels here to remove sorting later
     println!("creatures: {}", creatures.len());
@@ -142,6 +166,37 @@ fn read_level(lvl_name: &str) -> Level {
     Level::new(lvl_name.to_string(), voxels, day_part, creatures)
 }
 
+fn patrol_marker_names() -> Vec<String> {
+    MARKER_COLORS
+        .iter()
+        .flat_map(|color| {
+            [
+                format!("minecraft:{color}_carpet"),
+                format!("minecraft:{color}_banner"),
+            ]
+        })
+        .collect()
+}
+
+fn validate_level(lvl: &Level, collisions: &[BlockSequence]) {
+    let boxes: Vec<CollisionBox> = collisions
+        .iter()
+        .map(|seq| CollisionBox {
+            start: ivec3(seq.start.x as i32, seq.start.y as i32, seq.start.z as i32),
+            end: ivec3(seq.end.x as i32, seq.end.y as i32, seq.end.z as i32),
+        })
+        .collect();
+    let issues = validate(lvl, &boxes);
+
+    for issue in &issues {
+        println!("cargo:warning={} {issue}", lvl.name);
+    }
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+    if errors > 0 {
+        panic!("Level {} has {errors} errors, see the warnings above", lvl.name);
+    }
+}
+
 fn match_name_to_material(name: &str) -> Material {
     match name {
         "minecraft:glass" | "minecraft:glass_pane" |
@@ -0,0 +1,47 @@
+use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Read};
+
+use flate2::read::ZlibDecoder;
+use lib::entity::level::Level;
+use lib::util::map_dump::{LevelMap, MapProjection};
+
+const LVL_DIR: &str = "./assets/lvl/";
+const USAGE: &str = "Usage: level_map <lvl name> [--slice <y>] [--png <file>] [--scale <pixels>]";
+
+/// Prints a top-down (or a single plate) map of a level, or saves it as PNG.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let lvl_name = args.first().expect(USAGE);
+    let option = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|i| args.get(i + 1).expect(USAGE).as_str())
+    };
+
+    let projection = option("--slice").map_or(MapProjection::TopDown, |y| {
+        MapProjection::Slice(y.parse().expect("Slice should be a positive number"))
+    });
+    let scale = option("--scale").map_or(4, |s| s.parse().expect("Scale should be a number"));
+
+    let map = LevelMap::new(&read_level(lvl_name), projection);
+    if let Some(path) = option("--png") {
+        let file = File::create(path).expect("Cannot create png file");
+        map.write_png(BufWriter::new(file), scale)
+            .expect("Cannot write png");
+    } else {
+        print!("{}", map.to_ascii());
+    }
+}
+
+fn read_level(lvl_name: &str) -> Level {
+    let path = [LVL_DIR, lvl_name, "/lvl.json.gz"].concat();
+    let lvl_file = File::open(path).expect("Can't open file");
+
+    let mut json = String::new();
+    ZlibDecoder::new(lvl_file)
+        .read_to_string(&mut json)
+        .expect("Failed to decode lvl");
+
+    serde_json::from_str(&json).expect("Failed to parse lvl")
+}
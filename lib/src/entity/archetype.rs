@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::damage::{DamageType, ResistanceParams};
+use crate::entity::level::creature::{CreatureName, CreatureRelation};
+use crate::util::json_dir::read_named_json_dir;
+
+/// Everything that makes a kind of creature, one JSON file per archetype.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatureArchetype {
+    pub name: CreatureName,
+    pub mesh: String,
+    /// Scale of the mesh only, the body size is `physiology`
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Degrees the body is turned by around the vertical axis when spawned, until it moves
+    #[serde(default = "default_yaw")]
+    pub yaw: f32,
+    pub hp: u16,
+    #[serde(default)]
+    pub resistances: ResistanceParams,
+    /// The collider, the HP bar and the eyes follow the size of the body
+    #[serde(default)]
+    pub physiology: PhysiologyParams,
+    #[serde(default)]
+    pub perception: PerceptionParams,
+    /// The first usable attack of the list is chosen
+    #[serde(default)]
+    pub attacks: Vec<AttackParams>,
+    /// Movement when nothing happens, patrol routes from the level replace it
+    #[serde(default)]
+    pub movement: MovementKind,
+    /// Creatures without behaviour never change their movement
+    #[serde(default)]
+    pub behaviour: Option<BehaviourParams>,
+    pub relation: CreatureRelation,
+    /// Block which marks a spawn of the creature in the original level
+    #[serde(default)]
+    pub marker: Option<String>,
+    #[serde(default)]
+    pub death: DeathParams,
+    /// Points the player gets for the kill
+    #[serde(default)]
+    pub score: u32,
+    #[serde(default)]
+    pub loot: Vec<LootEntry>,
+    /// Bosses change the way they fight by phases and get a big HP bar
+    #[serde(default)]
+    pub boss: Option<BossParams>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PhysiologyParams {
+    pub model_height: f32,
+    pub model_radius: f32,
+    pub jump_height: f32,
+    pub movement_speed: f32,
+    /// Above the center of the body, the top of the body if not set
+    pub eyes_height: Option<f32>,
+}
+
+impl Default for PhysiologyParams {
+    fn default() -> Self {
+        PhysiologyParams {
+            model_height: 2.0,
+            model_radius: 0.5,
+            jump_height: 1.0,
+            movement_speed: 5.,
+            eyes_height: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PerceptionParams {
+    pub sight_range: f32,
+    pub dark_sight_range: f32,
+    /// Degrees
+    pub field_of_view: f32,
+    pub hearing_range: f32,
+    /// Seconds
+    pub memory: f32,
+}
+
+impl Default for PerceptionParams {
+    fn default() -> Self {
+        PerceptionParams {
+            sight_range: 15.0,
+            dark_sight_range: 6.0,
+            field_of_view: 120.0,
+            hearing_range: 10.0,
+            memory: 5.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AttackParams {
+    pub kind: AttackKind,
+    /// Distances are measured from the body of the creature
+    #[serde(default)]
+    pub min_range: f32,
+    pub range: f32,
+    /// Seconds
+    pub cooldown: f32,
+    pub damage: u16,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// From 0 to 1, critical hits deal double damage
+    #[serde(default)]
+    pub crit_chance: f32,
+    /// Seconds between choosing the attack and using it, the aim is taken at the start
+    #[serde(default)]
+    pub wind_up: f32,
+    /// Distance per second
+    #[serde(default = "default_projectile_speed")]
+    pub projectile_speed: f32,
+    #[serde(default)]
+    pub projectile: ProjectileParams,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct ProjectileParams {
+    /// Distance per second squared, only lobbed projectiles fall if not set
+    pub gravity: Option<f32>,
+    /// The projectile disappears after flying so far or so many seconds
+    pub max_range: f32,
+    pub lifetime: f32,
+    /// Targets the projectile flies through after the first one
+    pub pierce: u8,
+    /// Bounces off the walls before breaking
+    pub ricochet: u8,
+}
+
+impl Default for ProjectileParams {
+    fn default() -> Self {
+        ProjectileParams {
+            gravity: None,
+            max_range: 50.0,
+            lifetime: 5.0,
+            pierce: 0,
+            ricochet: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "type")]
+pub enum AttackKind {
+    /// Strikes everything in `range` in front of the creature
+    Melee,
+    SingleShot,
+    /// `shots` bullets one after another, `interval` seconds apart
+    Burst { shots: u32, interval: f32 },
+    /// `bullets` at once, fanned over `angle` degrees
+    Spread { bullets: u32, angle: f32 },
+    /// Flies by an arc, so it's thrown at the last known position even over cover
+    Lobbed,
+}
+
+impl AttackKind {
+    pub fn needs_sight(self) -> bool {
+        self != AttackKind::Lobbed
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum MovementKind {
+    #[default]
+    Stand,
+    Random,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BehaviourParams {
+    /// Distance kept to the player while chasing, 0 for melee creatures
+    pub preferred_distance: f32,
+    pub attack_range: f32,
+    /// Seconds
+    pub reaction_time: f32,
+    /// Seconds
+    pub alert_duration: f32,
+    /// Part of HP below which the creature runs away, 0 for the brave ones
+    pub flee_below_hp: f32,
+    pub flee_distance: f32,
+}
+
+impl Default for BehaviourParams {
+    fn default() -> Self {
+        BehaviourParams {
+            preferred_distance: 0.0,
+            attack_range: 2.0,
+            reaction_time: 0.3,
+            alert_duration: 3.0,
+            flee_below_hp: 0.0,
+            flee_distance: 20.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DeathParams {
+    pub animation: Option<String>,
+    /// Seconds the body lies before despawn
+    pub corpse_time: f32,
+    /// The body falls over by physics instead of standing still
+    pub ragdoll: bool,
+}
+
+impl Default for DeathParams {
+    fn default() -> Self {
+        DeathParams {
+            animation: None,
+            corpse_time: 3.0,
+            ragdoll: true,
+        }
+    }
+}
+
+/// Item dropped on death with the `chance` from 0 to 1.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LootEntry {
+    pub item: String,
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+    #[serde(default = "default_count")]
+    pub min: u16,
+    #[serde(default = "default_count")]
+    pub max: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BossParams {
+    /// Shown above the HP bar
+    pub title: String,
+    /// Sorted by `hp_below` from the highest, the first one usually starts at full HP
+    pub phases: Vec<BossPhaseParams>,
+}
+
+impl BossParams {
+    /// The last phase whose threshold the part of HP has fallen to.
+    pub fn phase_at(&self, hp: f32) -> Option<usize> {
+        self.phases.iter().rposition(|phase| hp <= phase.hp_below)
+    }
+
+    /// `phase_at` relies on it, so the archetypes are checked on load.
+    pub fn phases_are_sorted(&self) -> bool {
+        self.phases
+            .windows(2)
+            .all(|pair| pair[0].hp_below >= pair[1].hp_below)
+    }
+
+    /// The speed buff divides by the multiplier when it's over, zero or less would break it.
+    pub fn speeds_are_positive(&self) -> bool {
+        self.phases
+            .iter()
+            .all(|phase| phase.speed_multiplier.is_finite() && phase.speed_multiplier > 0.)
+    }
+}
+
+/// Everything not set stays as in the previous phase.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BossPhaseParams {
+    /// Part of HP from 0 to 1 the phase starts at
+    pub hp_below: f32,
+    #[serde(default)]
+    pub attacks: Option<Vec<AttackParams>>,
+    /// Replaces the behaviour with its chase and flee movement
+    #[serde(default)]
+    pub behaviour: Option<BehaviourParams>,
+    /// Multiplies the movement speed while the phase lasts
+    #[serde(default = "default_multiplier")]
+    pub speed_multiplier: f32,
+}
+
+fn default_multiplier() -> f32 {
+    1.0
+}
+
+fn default_chance() -> f32 {
+    1.0
+}
+
+fn default_count() -> u16 {
+    1
+}
+
+fn default_projectile_speed() -> f32 {
+    6.0
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_yaw() -> f32 {
+    90.0
+}
+
+#[derive(Debug, Default)]
+pub struct CreatureArchetypes {
+    archetypes: HashMap<CreatureName, CreatureArchetype>,
+}
+
+impl CreatureArchetypes {
+    /// Archetypes of the dir by their names, bosses with broken phases are rejected.
+    pub fn from_dir(path: &str) -> CreatureArchetypes {
+        let mut archetypes = HashMap::new();
+        let all = read_named_json_dir(path, "creature archetype", |a: &CreatureArchetype| {
+            a.name.0.as_str()
+        });
+
+        for archetype in all {
+            if let Some(boss) = &archetype.boss {
+                if !boss.phases_are_sorted() {
+                    panic!(
+                        "Boss phases of {} must be sorted by hp_below from the highest",
+                        archetype.name
+                    );
+                }
+                if !boss.speeds_are_positive() {
+                    panic!(
+                        "Boss phases of {} must have a speed_multiplier above 0",
+                        archetype.name
+                    );
+                }
+            }
+            archetypes.insert(archetype.name.clone(), archetype);
+        }
+
+        CreatureArchetypes { archetypes }
+    }
+
+    pub fn get(&self, name: &CreatureName) -> Option<&CreatureArchetype> {
+        self.archetypes.get(name)
+    }
+
+    /// Archetype spawned by the block in the original level.
+    pub fn by_marker(&self, block_name: &str) -> Option<&CreatureArchetype> {
+        self.archetypes
+            .values()
+            .find(|archetype| archetype.marker.as_deref() == Some(block_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_params_are_default() {
+        let archetype: CreatureArchetype = serde_json::from_str(
+            r#"{
+                "name": "Rat",
+                "mesh": "mesh/rat.glb#Scene0",
+                "hp": 10,
+                "physiology": { "movement_speed": 8.0 },
+                "relation": "Neutral"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(archetype.name, CreatureName::from("Rat"));
+        assert_eq!(archetype.physiology.movement_speed, 8.0);
+        assert_eq!(archetype.yaw, 90.0);
+        assert_eq!(archetype.physiology.model_height, 2.0);
+        assert!(archetype.attacks.is_empty());
+        assert!(archetype.behaviour.is_none());
+        assert_eq!(archetype.death.corpse_time, 3.0);
+        assert!(archetype.loot.is_empty());
+    }
+
+    #[test]
+    fn boss_phases_follow_hp() {
+        let boss: BossParams = serde_json::from_str(
+            r#"{
+                "title": "Rat King",
+                "phases": [
+                    { "hp_below": 1.0 },
+                    { "hp_below": 0.6, "speed_multiplier": 1.5 },
+                    { "hp_below": 0.3, "attacks": [] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(boss.phase_at(1.0), Some(0));
+        assert_eq!(boss.phase_at(0.6), Some(1));
+        assert_eq!(boss.phases[1].speed_multiplier, 1.5);
+        assert_eq!(boss.phase_at(0.1), Some(2));
+        assert!(boss.phases[2].attacks.is_some());
+        assert!(boss.phases_are_sorted());
+        assert!(boss.speeds_are_positive());
+    }
+
+    #[test]
+    fn stopping_boss_phases_are_found() {
+        for multiplier in ["0.0", "-1.5"] {
+            let boss: BossParams = serde_json::from_str(&format!(
+                r#"{{
+                    "title": "Rat King",
+                    "phases": [
+                        {{ "hp_below": 1.0 }},
+                        {{ "hp_below": 0.5, "speed_multiplier": {multiplier} }}
+                    ]
+                }}"#
+            ))
+            .unwrap();
+
+            assert!(!boss.speeds_are_positive());
+        }
+    }
+
+    #[test]
+    fn unsorted_boss_phases_are_found() {
+        let boss: BossParams = serde_json::from_str(
+            r#"{
+                "title": "Rat King",
+                "phases": [
+                    { "hp_below": 0.3 },
+                    { "hp_below": 1.0 }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(!boss.phases_are_sorted());
+    }
+
+    #[test]
+    fn repo_archetypes_are_valid() {
+        let archetypes = CreatureArchetypes::from_dir(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/creature"
+        ));
+
+        assert!(archetypes.get(&CreatureName::from("Pizza")).is_some());
+        assert!(archetypes.by_marker("minecraft:oak_sign").is_some());
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Poison,
+    Electric,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ResistanceParams {
+    /// Taken from every physical hit
+    pub armor: u16,
+    /// Part of the damage of the type which is ignored, 1 for immunity, negative for weakness
+    pub resist: HashMap<DamageType, f32>,
+}
+
+impl ResistanceParams {
+    /// What is left of the hit, at least 1 unless the target is immune.
+    pub fn reduce(&self, amount: u16, damage_type: DamageType) -> u16 {
+        let resist = self.resist.get(&damage_type).copied().unwrap_or(0.);
+        if amount == 0 || resist >= 1. {
+            return 0;
+        }
+
+        let armor = if damage_type == DamageType::Physical {
+            self.armor
+        } else {
+            0
+        };
+        let amount = f32::from(amount.saturating_sub(armor)) * (1. - resist);
+        (amount.round() as u16).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn armor_and_resistances_reduce_damage() {
+        let params: ResistanceParams = serde_json::from_str(
+            r#"{ "armor": 3, "resist": { "Fire": 0.5, "Poison": 1.0, "Electric": -1.0 } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(params.reduce(10, DamageType::Physical), 7);
+        assert_eq!(params.reduce(2, DamageType::Physical), 1);
+        assert_eq!(params.reduce(10, DamageType::Fire), 5);
+        assert_eq!(params.reduce(10, DamageType::Poison), 0);
+        assert_eq!(params.reduce(10, DamageType::Electric), 20);
+        assert_eq!(ResistanceParams::default().reduce(10, DamageType::Fire), 10);
+    }
+}
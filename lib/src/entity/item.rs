@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::util::json_dir::read_named_json_dir;
+
+/// Anything the player can pick up and carry, one JSON file per item.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemParams {
+    /// Loot of the creatures refers to the item by it
+    pub name: String,
+    /// Shown in the inventory instead of the name
+    #[serde(default)]
+    pub title: Option<String>,
+    /// How many of the item fit in one slot of the inventory
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u16,
+    #[serde(default)]
+    pub kind: ItemKind,
+}
+
+fn default_max_stack() -> u16 {
+    10
+}
+
+impl ItemParams {
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(tag = "type")]
+pub enum ItemKind {
+    /// Only lies in the inventory
+    #[default]
+    Misc,
+    /// Used up to heal the player
+    Consumable { heal: u16 },
+}
+
+/// Some of one item in a slot of the inventory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ItemStack {
+    pub item: String,
+    pub count: u16,
+}
+
+#[derive(Debug, Default)]
+pub struct Items {
+    items: Vec<ItemParams>,
+}
+
+impl Items {
+    /// Items of the dir, the loot and the save refer to them by the name.
+    pub fn from_dir(path: &str) -> Items {
+        let items = read_named_json_dir(path, "item", |item: &ItemParams| &item.name);
+        Items { items }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ItemParams> {
+        self.items.iter().find(|item| item.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_default_to_misc() {
+        let item: ItemParams = serde_json::from_str(r#"{ "name": "bone" }"#).unwrap();
+        assert_eq!(item.title(), "bone");
+        assert_eq!(item.kind, ItemKind::Misc);
+        assert_eq!(item.max_stack, 10);
+    }
+}
@@ -1,19 +1,32 @@
 use bevy::math::Vec3;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Creature {
     relation: CreatureRelation,
     pub name: CreatureName,
     pub position: Vec3,
+    #[serde(default)]
+    pub patrol: Option<PatrolRoute>,
 }
 
 impl Creature {
+    pub fn new(name: CreatureName, relation: CreatureRelation, position: Vec3) -> Creature {
+        Creature {
+            relation,
+            name,
+            position,
+            patrol: None,
+        }
+    }
+
     pub fn neytral(name: CreatureName, position: Vec3) -> Creature {
         Creature {
             relation: CreatureRelation::Neutral,
             name,
             position,
+            patrol: None,
         }
     }
     pub fn enemy(name: CreatureName, position: Vec3) -> Creature {
@@ -21,14 +34,40 @@ impl Creature {
             relation: CreatureRelation::Enemy,
             name,
             position,
+            patrol: None,
         }
     }
 
+    pub fn relation(&self) -> CreatureRelation {
+        self.relation
+    }
+
     pub fn is_enemy(&self) -> bool {
         self.relation == CreatureRelation::Enemy
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PatrolRoute {
+    pub waypoints: Vec<Waypoint>,
+    pub mode: PatrolMode,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug)]
+pub struct Waypoint {
+    pub position: Vec3,
+    /// Seconds to stand still after the waypoint is reached
+    pub wait: f32,
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub enum PatrolMode {
+    /// 1 → 2 → 3 → 1 → 2 ...
+    Loop,
+    /// 1 → 2 → 3 → 2 → 1 ...
+    PingPong,
+}
+
 #[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub enum CreatureRelation {
     Neutral,
@@ -36,8 +75,19 @@ pub enum CreatureRelation {
     Enemy,
 }
 
-#[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
-pub enum CreatureName {
-    Dummy,
-    Pizza,
+/// Name of the creature archetype, see `entity::archetype`.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct CreatureName(pub String);
+
+impl From<&str> for CreatureName {
+    fn from(name: &str) -> Self {
+        CreatureName(name.to_string())
+    }
+}
+
+impl Display for CreatureName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
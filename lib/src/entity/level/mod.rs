@@ -6,7 +6,11 @@ use bevy::math::Vec3;
 use serde::{Deserialize, Serialize};
 
 pub mod creature;
+pub mod navigation;
+pub mod patrol;
+pub mod spawn;
 pub mod voxel_plate;
+pub mod validation;
 pub mod voxel_stack;
 
 #[derive(Serialize, Deserialize)]
@@ -0,0 +1,391 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::RwLock;
+
+use bevy::math::{ivec3, IVec3, Vec3};
+
+use crate::entity::level::Level;
+use crate::entity::voxel::Material;
+use crate::util::math::{voxel_at, voxel_center};
+
+// Creatures survive falls from this height, deeper drops are not used by paths
+const MAX_FALL_HEIGHT: i32 = 3;
+// How many points A* may expand before giving up, keeps a single search cheap
+const MAX_SEARCH_POINTS: usize = 4096;
+// How far from the requested height the nearest walkable point is looked up
+const SNAP_HEIGHT: i32 = 2;
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+const CLIMB_COST: u32 = 5;
+const DIRECTIONS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodySize {
+    pub height: f32,
+    pub radius: f32,
+    pub jump_height: f32,
+}
+
+impl Default for BodySize {
+    fn default() -> Self {
+        BodySize {
+            height: 2.0,
+            radius: 0.5,
+            jump_height: 1.0,
+        }
+    }
+}
+
+impl BodySize {
+    fn height_in_voxels(&self) -> i32 {
+        (self.height.ceil() as i32).max(1)
+    }
+
+    // How many voxels around the center are covered by the body
+    fn reach_in_voxels(&self) -> i32 {
+        ((self.radius - 0.5).ceil() as i32).max(0)
+    }
+
+    fn step_in_voxels(&self) -> i32 {
+        self.jump_height.floor() as i32
+    }
+
+    fn cache_key(&self) -> (u16, u16, u16) {
+        (
+            (self.height * 10.) as u16,
+            (self.radius * 10.) as u16,
+            (self.jump_height * 10.) as u16,
+        )
+    }
+}
+
+/// A* over the walkable surface of a level.
+///
+/// Walkability of every checked point is cached per body size, so the same
+/// instance should be shared between all creatures of the level.
+#[derive(Default)]
+pub struct Pathfinder {
+    walkability: RwLock<HashMap<((u16, u16, u16), IVec3), bool>>,
+}
+
+impl Pathfinder {
+    /// Path from `from` to `to` including both ends, positions are the voxel points the body stands in.
+    pub fn find_path(
+        &self,
+        level: &Level,
+        body: &BodySize,
+        from: Vec3,
+        to: Vec3,
+    ) -> Option<Vec<Vec3>> {
+        let start = self.nearest_walkable(level, body, from)?;
+        let goal = self.nearest_walkable(level, body, to)?;
+
+        let mut open = BinaryHeap::from([Node {
+            point: start,
+            cost: 0,
+            estimate: heuristic(start, goal),
+        }]);
+        let mut came_from: HashMap<IVec3, IVec3> = HashMap::new();
+        let mut costs: HashMap<IVec3, u32> = HashMap::from([(start, 0)]);
+
+        while let Some(Node { point, cost, .. }) = open.pop() {
+            if point == goal {
+                return Some(reconstruct_path(&came_from, goal));
+            }
+            if costs.len() > MAX_SEARCH_POINTS {
+                return None;
+            }
+            if cost > costs[&point] {
+                continue;
+            }
+
+            for (next, step_cost) in self.neighbours(level, body, point) {
+                let next_cost = cost + step_cost;
+                if costs.get(&next).map_or(true, |known| next_cost < *known) {
+                    costs.insert(next, next_cost);
+                    came_from.insert(next, point);
+                    open.push(Node {
+                        point: next,
+                        cost: next_cost,
+                        estimate: next_cost + heuristic(next, goal),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Walkable points which can be reached from `point` in one step, with the cost of the step.
+    pub fn neighbours(&self, level: &Level, body: &BodySize, point: IVec3) -> Vec<(IVec3, u32)> {
+        let mut result = vec![];
+
+        for (dx, dz) in DIRECTIONS {
+            let diagonal = dx != 0 && dz != 0;
+            if diagonal
+                && !(self.is_passable(level, body, point + ivec3(dx, 0, 0))
+                    && self.is_passable(level, body, point + ivec3(0, 0, dz)))
+            {
+                // do not cut corners
+                continue;
+            }
+
+            for dy in (-MAX_FALL_HEIGHT..=body.step_in_voxels()).rev() {
+                let next = point + ivec3(dx, dy, dz);
+                if dy > 0 && !self.has_headroom(level, body, point, dy) {
+                    continue;
+                }
+                if self.is_walkable(level, body, next) {
+                    let mut cost = if diagonal {
+                        DIAGONAL_COST
+                    } else {
+                        STRAIGHT_COST
+                    };
+                    if dy > 0 {
+                        cost += CLIMB_COST * dy as u32;
+                    }
+                    result.push((next, cost));
+                    break;
+                }
+                if dy <= 0 && !self.is_passable(level, body, next) {
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+
+    /// The body fits into the point and has a floor under it.
+    pub fn is_walkable(&self, level: &Level, body: &BodySize, point: IVec3) -> bool {
+        let key = (body.cache_key(), point);
+        if let Some(walkable) = self.walkability.read().unwrap().get(&key) {
+            return *walkable;
+        }
+
+        let has_floor = is_floor(level, point - IVec3::Y);
+        let walkable = has_floor && self.is_passable(level, body, point);
+        self.walkability.write().unwrap().insert(key, walkable);
+
+        walkable
+    }
+
+    pub fn nearest_walkable(
+        &self,
+        level: &Level,
+        body: &BodySize,
+        position: Vec3,
+    ) -> Option<IVec3> {
+        let point = voxel_at(position);
+
+        (0..=SNAP_HEIGHT)
+            .flat_map(|dy| [point - IVec3::Y * dy, point + IVec3::Y * dy])
+            .find(|p| self.is_walkable(level, body, *p))
+    }
+
+    pub fn clear(&self) {
+        self.walkability.write().unwrap().clear();
+    }
+
+    fn is_passable(&self, level: &Level, body: &BodySize, point: IVec3) -> bool {
+        let reach = body.reach_in_voxels();
+
+        (0..body.height_in_voxels()).all(|dy| {
+            (-reach..=reach)
+                .all(|dx| (-reach..=reach).all(|dz| is_empty(level, point + ivec3(dx, dy, dz))))
+        })
+    }
+
+    // The whole body rises before stepping up, not only its center
+    fn has_headroom(&self, level: &Level, body: &BodySize, point: IVec3, climb: i32) -> bool {
+        let reach = body.reach_in_voxels();
+        let top = point.y + body.height_in_voxels();
+
+        (top..top + climb).all(|y| {
+            (-reach..=reach).all(|dx| {
+                (-reach..=reach).all(|dz| is_empty(level, ivec3(point.x + dx, y, point.z + dz)))
+            })
+        })
+    }
+}
+
+fn is_empty(level: &Level, point: IVec3) -> bool {
+    point.min_element() < 0 || level.get_voxel_by_point(&point.as_vec3()).is_none()
+}
+
+fn is_floor(level: &Level, point: IVec3) -> bool {
+    point.min_element() >= 0
+        && level
+            .get_voxel_by_point(&point.as_vec3())
+            .map_or(false, |voxel| voxel.material != Material::Water)
+}
+
+// Octile distance, never overestimates with the costs above
+fn heuristic(from: IVec3, to: IVec3) -> u32 {
+    let dx = (from.x - to.x).unsigned_abs();
+    let dz = (from.z - to.z).unsigned_abs();
+
+    STRAIGHT_COST * dx.max(dz) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dz)
+}
+
+fn reconstruct_path(came_from: &HashMap<IVec3, IVec3>, goal: IVec3) -> Vec<Vec3> {
+    let mut path = vec![voxel_center(goal)];
+    let mut current = goal;
+    while let Some(previous) = came_from.get(&current) {
+        path.push(voxel_center(*previous));
+        current = *previous;
+    }
+    path.reverse();
+
+    path
+}
+
+#[derive(PartialEq, Eq)]
+struct Node {
+    point: IVec3,
+    cost: u32,
+    estimate: u32,
+}
+
+// Reversed, so BinaryHeap pops the cheapest node first
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| other.cost.cmp(&self.cost))
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::level::DayPart;
+    use crate::entity::voxel::{Shape, Voxel};
+    use bevy::math::vec3;
+
+    fn level_with_wall(wall_height: usize) -> Level {
+        let mut voxels = vec![];
+        for x in 0..10 {
+            for z in 0..10 {
+                voxels.push(Voxel::new(
+                    vec3(x as f32, 0., z as f32),
+                    Material::Solid,
+                    Shape::Cube,
+                ));
+            }
+        }
+        // wall along x = 5 with a gap at z = 9
+        for z in 0..9 {
+            for y in 1..=wall_height {
+                voxels.push(Voxel::new(
+                    vec3(5., y as f32, z as f32),
+                    Material::Solid,
+                    Shape::Cube,
+                ));
+            }
+        }
+
+        Level::new("test".to_string(), voxels, DayPart::Day, vec![])
+    }
+
+    #[test]
+    fn goes_around_high_wall() {
+        let level = level_with_wall(3);
+        let path = Pathfinder::default()
+            .find_path(
+                &level,
+                &BodySize::default(),
+                vec3(2., 1., 2.),
+                vec3(8., 1., 2.),
+            )
+            .unwrap();
+
+        assert_eq!(path.first(), Some(&vec3(2.5, 1., 2.5)));
+        assert_eq!(path.last(), Some(&vec3(8.5, 1., 2.5)));
+        assert!(path.iter().any(|p| p.x == 5.5 && p.z == 9.5));
+    }
+
+    #[test]
+    fn jumps_over_low_wall() {
+        let level = level_with_wall(1);
+        let path = Pathfinder::default()
+            .find_path(
+                &level,
+                &BodySize::default(),
+                vec3(2., 1., 2.),
+                vec3(8., 1., 2.),
+            )
+            .unwrap();
+
+        assert!(path.contains(&vec3(5.5, 2., 2.5)));
+    }
+
+    #[test]
+    fn cannot_jump_without_jump_height() {
+        let level = level_with_wall(1);
+        let body = BodySize {
+            jump_height: 0.,
+            ..Default::default()
+        };
+        let path = Pathfinder::default()
+            .find_path(&level, &body, vec3(2., 1., 2.), vec3(8., 1., 2.))
+            .unwrap();
+
+        assert!(path.iter().all(|p| p.y == 1.));
+    }
+
+    #[test]
+    fn wide_body_needs_room_for_its_whole_width() {
+        let mut level_voxels = vec![];
+        for x in 0..10 {
+            for z in 0..10 {
+                level_voxels.push(Voxel::new(
+                    vec3(x as f32, 0., z as f32),
+                    Material::Solid,
+                    Shape::Cube,
+                ));
+            }
+        }
+        // low ceiling above the side of the body, not above its center
+        level_voxels.push(Voxel::new(vec3(4., 3., 5.), Material::Solid, Shape::Cube));
+        let level = Level::new("test".to_string(), level_voxels, DayPart::Day, vec![]);
+        let pathfinder = Pathfinder::default();
+        let wide = BodySize {
+            radius: 1.5,
+            ..Default::default()
+        };
+
+        let center = ivec3(5, 1, 5);
+        assert!(pathfinder.has_headroom(&level, &BodySize::default(), center, 1));
+        assert!(!pathfinder.has_headroom(&level, &wide, center, 1));
+    }
+
+    #[test]
+    fn wide_body_does_not_squeeze_through_gap() {
+        let level = level_with_wall(3);
+        let wide = BodySize {
+            radius: 1.5,
+            ..Default::default()
+        };
+
+        let path =
+            Pathfinder::default().find_path(&level, &wide, vec3(2., 1., 4.), vec3(8., 1., 4.));
+        assert!(path.is_none());
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use bevy::math::Vec3;
+
+use crate::entity::level::creature::{Creature, PatrolMode, PatrolRoute, Waypoint};
+
+/// Route markers which didn't become a route.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PatrolRouteIssue {
+    UnknownMarker(String),
+    NoCreature(String),
+}
+
+impl Display for PatrolRouteIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatrolRouteIssue::UnknownMarker(name) => {
+                write!(f, "Patrol route {name} is not a route marker")
+            }
+            PatrolRouteIssue::NoCreature(name) => write!(f, "Patrol route {name} has no creature"),
+        }
+    }
+}
+
+/// Carpets of one color make a looped route, standing banners of one color a ping-pong one.
+pub fn patrol_mode(name: &str) -> Option<PatrolMode> {
+    if name.ends_with("_carpet") {
+        Some(PatrolMode::Loop)
+    } else if name.ends_with("_banner") && !name.ends_with("_wall_banner") {
+        Some(PatrolMode::PingPong)
+    } else {
+        None
+    }
+}
+
+/// Every route belongs to the nearest creature without a route,
+/// waypoints are ordered by walking to the nearest unvisited marker.
+pub fn attach_patrol_routes(
+    creatures: &mut [Creature],
+    markers: HashMap<String, Vec<Vec3>>,
+    wait: f32,
+) -> Vec<PatrolRouteIssue> {
+    let mut markers: Vec<(String, Vec<Vec3>)> = markers.into_iter().collect();
+    markers.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut issues = vec![];
+
+    for (name, mut points) in markers {
+        let mode = if let Some(mode) = patrol_mode(&name) {
+            mode
+        } else {
+            issues.push(PatrolRouteIssue::UnknownMarker(name));
+            continue;
+        };
+        if points.is_empty() {
+            continue;
+        }
+        let centre = points.iter().fold(Vec3::ZERO, |sum, p| sum + *p) / points.len() as f32;
+        let creature = creatures
+            .iter_mut()
+            .filter(|creature| creature.patrol.is_none())
+            .min_by(|a, b| {
+                let a = a.position.distance(centre);
+                let b = b.position.distance(centre);
+                a.partial_cmp(&b).unwrap()
+            });
+        let creature = if let Some(creature) = creature {
+            creature
+        } else {
+            issues.push(PatrolRouteIssue::NoCreature(name));
+            continue;
+        };
+
+        let mut waypoints = vec![];
+        let mut current = creature.position;
+        while !points.is_empty() {
+            let (index, _) = points
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    a.distance(current)
+                        .partial_cmp(&b.distance(current))
+                        .unwrap()
+                })
+                .unwrap();
+            current = points.swap_remove(index);
+            waypoints.push(Waypoint {
+                position: current,
+                wait,
+            });
+        }
+
+        creature.patrol = Some(PatrolRoute { waypoints, mode });
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::vec3;
+
+    fn guard(x: f32) -> Creature {
+        Creature::enemy("Guard".into(), vec3(x, 1., 0.))
+    }
+
+    fn route_of(creature: &Creature) -> Vec<Vec3> {
+        let route = creature.patrol.as_ref().unwrap();
+        route
+            .waypoints
+            .iter()
+            .map(|waypoint| waypoint.position)
+            .collect()
+    }
+
+    #[test]
+    fn markers_choose_the_mode() {
+        assert_eq!(patrol_mode("minecraft:red_carpet"), Some(PatrolMode::Loop));
+        assert_eq!(
+            patrol_mode("minecraft:red_banner"),
+            Some(PatrolMode::PingPong)
+        );
+        assert_eq!(patrol_mode("minecraft:red_wall_banner"), None);
+        assert_eq!(patrol_mode("minecraft:stone"), None);
+    }
+
+    #[test]
+    fn routes_go_to_the_nearest_creatures() {
+        let mut creatures = vec![guard(0.), guard(20.)];
+        let markers = HashMap::from([
+            (
+                "minecraft:red_carpet".to_string(),
+                vec![vec3(4., 1., 0.), vec3(2., 1., 0.), vec3(3., 1., 2.)],
+            ),
+            ("minecraft:blue_banner".to_string(), vec![vec3(21., 1., 0.)]),
+        ]);
+
+        let issues = attach_patrol_routes(&mut creatures, markers, 1.);
+        assert!(issues.is_empty());
+        assert_eq!(
+            route_of(&creatures[0]),
+            vec![vec3(2., 1., 0.), vec3(4., 1., 0.), vec3(3., 1., 2.)]
+        );
+        assert_eq!(creatures[0].patrol.as_ref().unwrap().mode, PatrolMode::Loop);
+        assert_eq!(route_of(&creatures[1]), vec![vec3(21., 1., 0.)]);
+        assert_eq!(
+            creatures[1].patrol.as_ref().unwrap().mode,
+            PatrolMode::PingPong
+        );
+    }
+
+    #[test]
+    fn unknown_and_extra_routes_are_reported() {
+        let mut creatures = vec![guard(0.)];
+        let markers = HashMap::from([
+            ("minecraft:red_carpet".to_string(), vec![vec3(2., 1., 0.)]),
+            ("minecraft:blue_carpet".to_string(), vec![vec3(5., 1., 0.)]),
+            ("minecraft:stone".to_string(), vec![vec3(1., 1., 0.)]),
+        ]);
+
+        let issues = attach_patrol_routes(&mut creatures, markers, 1.);
+        assert_eq!(
+            issues,
+            vec![
+                PatrolRouteIssue::NoCreature("minecraft:red_carpet".to_string()),
+                PatrolRouteIssue::UnknownMarker("minecraft:stone".to_string()),
+            ]
+        );
+        assert_eq!(route_of(&creatures[0]), vec![vec3(5., 1., 0.)]);
+    }
+}
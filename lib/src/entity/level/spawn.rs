@@ -0,0 +1,109 @@
+use crate::entity::level::creature::CreatureName;
+use bevy::math::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Creatures spawned while the level is played, `assets/lvl/<name>/spawns.json`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LevelSpawns {
+    #[serde(default)]
+    pub spawners: Vec<SpawnerParams>,
+    #[serde(default)]
+    pub arena: Option<ArenaParams>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpawnerParams {
+    pub creature: CreatureName,
+    pub position: Vec3,
+    /// Creatures spawned in total, 0 for endless
+    #[serde(default)]
+    pub count: u32,
+    /// Seconds between spawns
+    pub interval: f32,
+    pub max_alive: u32,
+    /// Works only while the player is closer
+    pub activation_radius: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArenaParams {
+    pub center: Vec3,
+    /// The first wave starts when the player comes closer
+    pub activation_radius: f32,
+    /// Creatures of a wave take the points in turn, the center is used if there are none
+    #[serde(default)]
+    pub spawn_points: Vec<Vec3>,
+    pub creatures: Vec<ArenaCreature>,
+    pub first_wave_size: u32,
+    /// Creatures added to every next wave
+    pub wave_growth: u32,
+    /// Seconds between a cleared wave and the next one
+    pub break_time: f32,
+    /// 0 for endless
+    #[serde(default)]
+    pub waves: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArenaCreature {
+    pub name: CreatureName,
+    /// Number of the first wave with the creature, counting from 1
+    #[serde(default)]
+    pub from_wave: u32,
+}
+
+impl ArenaParams {
+    /// Creatures of the wave, the later waves are bigger and mix in the creatures unlocked so far.
+    pub fn wave_creatures(&self, wave: u32) -> Vec<CreatureName> {
+        let unlocked: Vec<&CreatureName> = self
+            .creatures
+            .iter()
+            .filter(|creature| creature.from_wave <= wave)
+            .map(|creature| &creature.name)
+            .collect();
+        if unlocked.is_empty() {
+            return vec![];
+        }
+
+        let size = self.first_wave_size + self.wave_growth * wave.saturating_sub(1);
+        (0..size as usize)
+            .map(|i| unlocked[i % unlocked.len()].clone())
+            .collect()
+    }
+
+    pub fn is_last_wave(&self, wave: u32) -> bool {
+        self.waves != 0 && wave >= self.waves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waves_escalate() {
+        let arena: ArenaParams = serde_json::from_str(
+            r#"{
+                "center": [0.0, 0.0, 0.0],
+                "activation_radius": 5.0,
+                "creatures": [{ "name": "Pizza" }, { "name": "Rat", "from_wave": 3 }],
+                "first_wave_size": 2,
+                "wave_growth": 1,
+                "break_time": 5.0,
+                "waves": 3
+            }"#,
+        )
+        .unwrap();
+
+        let pizza = CreatureName::from("Pizza");
+        let rat = CreatureName::from("Rat");
+        assert_eq!(arena.wave_creatures(1), vec![pizza.clone(), pizza.clone()]);
+        assert_eq!(arena.wave_creatures(2).len(), 3);
+        assert_eq!(
+            arena.wave_creatures(3),
+            vec![pizza.clone(), rat.clone(), pizza, rat]
+        );
+        assert!(!arena.is_last_wave(2));
+        assert!(arena.is_last_wave(3));
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+use bevy::math::{ivec3, IVec3, Vec3};
+
+use crate::entity::level::creature::CreatureName;
+use crate::entity::level::navigation::{BodySize, Pathfinder};
+use crate::entity::level::Level;
+use crate::entity::voxel::Material;
+use crate::util::math::{touched_points, voxel_at};
+
+// Default creature body, the same as `PhysiologyDescription::default()` in the game
+const CREATURE_RADIUS: f32 = 0.5;
+const CREATURE_HEIGHT: i32 = 2;
+// Smaller walkable areas are mostly roofs and tops of pillars, reporting them is just noise
+const MIN_UNREACHABLE_AREA: usize = 4;
+const NEIGHBOURS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+/// Collision box in the same coordinates as the level voxels, both `start` and `end` are inclusive.
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionBox {
+    pub start: IVec3,
+    pub end: IVec3,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum IssueKind {
+    CreatureInsideBlocks(CreatureName),
+    UnreachableCreature(CreatureName),
+    UnreachableArea { size: usize },
+    BuriedLight(Material),
+    VoxelWithoutCollision(Material),
+    CollisionWithoutVoxels { empty_points: usize },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LevelIssue {
+    pub severity: Severity,
+    pub kind: IssueKind,
+    pub position: Vec3,
+}
+
+impl LevelIssue {
+    fn error(kind: IssueKind, position: Vec3) -> LevelIssue {
+        LevelIssue {
+            severity: Severity::Error,
+            kind,
+            position,
+        }
+    }
+
+    fn warning(kind: IssueKind, position: Vec3) -> LevelIssue {
+        LevelIssue {
+            severity: Severity::Warning,
+            kind,
+            position,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for LevelIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let message = match &self.kind {
+            IssueKind::CreatureInsideBlocks(name) => format!("{name} is spawned inside blocks"),
+            IssueKind::UnreachableCreature(name) => {
+                format!("{name} is spawned in an unreachable area")
+            }
+            IssueKind::UnreachableArea { size } => {
+                format!("walkable area of {size} points is unreachable")
+            }
+            IssueKind::BuriedLight(material) => {
+                format!("{material:?} has no empty neighbours and lights nothing")
+            }
+            IssueKind::VoxelWithoutCollision(material) => {
+                format!("{material:?} voxel is not covered by collisions.json")
+            }
+            IssueKind::CollisionWithoutVoxels { empty_points } => {
+                format!("collision box covers {empty_points} points without voxels")
+            }
+        };
+        let p = self.position;
+
+        write!(f, "{severity}: {message} at ({}, {}, {})", p.x, p.y, p.z)
+    }
+}
+
+pub fn validate(level: &Level, collisions: &[CollisionBox]) -> Vec<LevelIssue> {
+    let mut issues = vec![];
+
+    issues.extend(check_creatures_inside_blocks(level));
+    issues.extend(check_buried_lights(level));
+    issues.extend(check_collisions(level, collisions));
+    issues.extend(check_reachability(level));
+
+    issues
+}
+
+fn check_creatures_inside_blocks(level: &Level) -> Vec<LevelIssue> {
+    level
+        .creatures()
+        .iter()
+        .filter(|creature| {
+            let p = creature.position;
+            let body: Vec<Vec3> = (0..CREATURE_HEIGHT)
+                .flat_map(|height| touched_points(p.x, p.y + height as f32, p.z, CREATURE_RADIUS))
+                .collect();
+
+            !level.points_are_empty(&body)
+        })
+        .map(|creature| {
+            LevelIssue::error(
+                IssueKind::CreatureInsideBlocks(creature.name.clone()),
+                creature.position,
+            )
+        })
+        .collect()
+}
+
+fn check_buried_lights(level: &Level) -> Vec<LevelIssue> {
+    level
+        .lights()
+        .into_iter()
+        .filter(|light| {
+            let position = voxel_at(light.position);
+            NEIGHBOURS
+                .iter()
+                .all(|side| !is_empty(level, position + *side))
+        })
+        .map(|light| LevelIssue::warning(IssueKind::BuriedLight(light.material), light.position))
+        .collect()
+}
+
+fn check_collisions(level: &Level, collisions: &[CollisionBox]) -> Vec<LevelIssue> {
+    let mut issues = vec![];
+    let mut covered = HashSet::new();
+
+    for collision in collisions {
+        let mut empty_points = 0;
+        for x in collision.start.x..=collision.end.x {
+            for y in collision.start.y..=collision.end.y {
+                for z in collision.start.z..=collision.end.z {
+                    let point = ivec3(x, y, z);
+                    if is_empty(level, point) {
+                        empty_points += 1;
+                    }
+                    covered.insert(point);
+                }
+            }
+        }
+
+        if empty_points > 0 {
+            issues.push(LevelIssue::warning(
+                IssueKind::CollisionWithoutVoxels { empty_points },
+                collision.start.as_vec3(),
+            ));
+        }
+    }
+
+    for voxel in level.voxel_stack().voxels_by_material(&[Material::Solid]) {
+        if !covered.contains(&voxel_at(voxel.position)) {
+            issues.push(LevelIssue::error(
+                IssueKind::VoxelWithoutCollision(voxel.material),
+                voxel.position,
+            ));
+        }
+    }
+
+    issues
+}
+
+fn check_reachability(level: &Level) -> Vec<LevelIssue> {
+    let pathfinder = Pathfinder::default();
+    let body = BodySize::default();
+    let mut walkable: Vec<IVec3> = level
+        .voxel_stack()
+        .voxels_by_material(&[Material::Solid, Material::Passable])
+        .into_iter()
+        .map(|voxel| voxel_at(voxel.position) + IVec3::Y)
+        .filter(|point| pathfinder.is_walkable(level, &body, *point))
+        .collect();
+    // It's possible to jump down but not always to climb back,
+    // so areas are filled from the bottom and higher ones get only what is left
+    walkable.sort_by_key(|p| (p.y, p.x, p.z));
+
+    let mut area_of_point: HashMap<IVec3, usize> = HashMap::new();
+    let mut areas: Vec<Vec<IVec3>> = vec![];
+    for start in walkable {
+        if area_of_point.contains_key(&start) {
+            continue;
+        }
+        let area: Vec<IVec3> = flood_fill(level, &pathfinder, &body, start)
+            .into_iter()
+            .filter(|point| !area_of_point.contains_key(point))
+            .collect();
+        for point in &area {
+            area_of_point.insert(*point, areas.len());
+        }
+        areas.push(area);
+    }
+
+    let main_area = if let Some((index, _)) = areas.iter().enumerate().max_by_key(|(_, a)| a.len())
+    {
+        index
+    } else {
+        return vec![];
+    };
+
+    let mut issues: Vec<LevelIssue> = areas
+        .iter()
+        .enumerate()
+        .filter(|(index, area)| *index != main_area && area.len() >= MIN_UNREACHABLE_AREA)
+        .map(|(_, area)| {
+            let corner = area.iter().min_by_key(|p| (p.y, p.x, p.z)).unwrap();
+            LevelIssue::warning(
+                IssueKind::UnreachableArea { size: area.len() },
+                corner.as_vec3(),
+            )
+        })
+        .collect();
+
+    for creature in level.creatures() {
+        let point = voxel_at(creature.position);
+        let area = area_of_point
+            .get(&point)
+            .or_else(|| area_of_point.get(&(point + IVec3::Y)));
+        if area.map_or(false, |area| *area != main_area) {
+            issues.push(LevelIssue::warning(
+                IssueKind::UnreachableCreature(creature.name.clone()),
+                creature.position,
+            ));
+        }
+    }
+
+    issues
+}
+
+fn flood_fill(level: &Level, pathfinder: &Pathfinder, body: &BodySize, start: IVec3) -> Vec<IVec3> {
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(point) = queue.pop_front() {
+        for (next, _) in pathfinder.neighbours(level, body, point) {
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    visited.into_iter().collect()
+}
+
+fn is_empty(level: &Level, point: IVec3) -> bool {
+    point.min_element() < 0 || level.get_voxel_by_point(&point.as_vec3()).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::level::creature::Creature;
+    use crate::entity::level::DayPart;
+    use crate::entity::voxel::{Shape, Voxel};
+    use bevy::math::vec3;
+
+    fn plate(xs: std::ops::Range<i32>, y: i32, material: Material) -> Vec<Voxel> {
+        xs.flat_map(|x| {
+            (0..8).map(move |z| Voxel::new(ivec3(x, y, z).as_vec3(), material, Shape::Cube))
+        })
+        .collect()
+    }
+
+    fn level(voxels: Vec<Voxel>, creatures: Vec<Creature>) -> Level {
+        Level::new("test".to_string(), voxels, DayPart::Day, creatures)
+    }
+
+    fn covering(start: IVec3, end: IVec3) -> CollisionBox {
+        CollisionBox { start, end }
+    }
+
+    fn kinds(issues: &[LevelIssue]) -> Vec<IssueKind> {
+        issues.iter().map(|issue| issue.kind.clone()).collect()
+    }
+
+    #[test]
+    fn clean_level_has_no_issues() {
+        let pizza = Creature::enemy("Pizza".into(), vec3(3.5, 1., 3.5));
+        let level = level(plate(0..8, 0, Material::Solid), vec![pizza]);
+
+        let issues = validate(&level, &[covering(IVec3::ZERO, ivec3(7, 0, 7))]);
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn creature_inside_blocks_is_error() {
+        let mut voxels = plate(0..8, 0, Material::Solid);
+        voxels.push(Voxel::new(vec3(3., 1., 3.), Material::Solid, Shape::Cube));
+        let pizza = Creature::enemy("Pizza".into(), vec3(3.5, 1., 3.5));
+        let level = level(voxels, vec![pizza]);
+
+        let issues = check_creatures_inside_blocks(&level);
+        assert_eq!(
+            kinds(&issues),
+            vec![IssueKind::CreatureInsideBlocks("Pizza".into())]
+        );
+        assert!(issues[0].is_error());
+    }
+
+    #[test]
+    fn buried_light_is_warning() {
+        let mut voxels = plate(0..8, 0, Material::Solid);
+        voxels.extend(
+            plate(0..8, 1, Material::Solid)
+                .into_iter()
+                .filter(|voxel| voxel.position != vec3(3., 1., 3.)),
+        );
+        voxels.push(Voxel::new(
+            vec3(3., 1., 3.),
+            Material::OrangeLight,
+            Shape::Cube,
+        ));
+        voxels.push(Voxel::new(vec3(3., 2., 3.), Material::Solid, Shape::Cube));
+        // lights with an empty neighbour are fine
+        voxels.push(Voxel::new(
+            vec3(6., 2., 6.),
+            Material::BlueLight,
+            Shape::Cube,
+        ));
+        let level = level(voxels, vec![]);
+
+        let issues = check_buried_lights(&level);
+        assert_eq!(
+            kinds(&issues),
+            vec![IssueKind::BuriedLight(Material::OrangeLight)]
+        );
+        assert!(!issues[0].is_error());
+    }
+
+    #[test]
+    fn collisions_must_match_voxels() {
+        let level = level(plate(0..2, 0, Material::Solid), vec![]);
+        let collisions = [
+            covering(IVec3::ZERO, ivec3(0, 0, 7)),
+            covering(ivec3(0, 1, 0), ivec3(1, 1, 0)),
+        ];
+
+        let issues = check_collisions(&level, &collisions);
+        let uncovered = issues
+            .iter()
+            .filter(|issue| issue.kind == IssueKind::VoxelWithoutCollision(Material::Solid))
+            .count();
+        assert_eq!(uncovered, 8);
+        assert!(issues.contains(&LevelIssue::warning(
+            IssueKind::CollisionWithoutVoxels { empty_points: 2 },
+            vec3(0., 1., 0.),
+        )));
+    }
+
+    #[test]
+    fn separate_floor_is_unreachable() {
+        let mut voxels = plate(0..4, 0, Material::Solid);
+        // two voxels of the void between the floors can't be walked or jumped over
+        voxels.extend(plate(6..8, 0, Material::Solid));
+        let pizza = Creature::enemy("Pizza".into(), vec3(2.5, 1., 2.5));
+        let lost_pizza = Creature::enemy("Lost pizza".into(), vec3(7.5, 1., 3.5));
+        let level = level(voxels, vec![pizza, lost_pizza]);
+
+        let issues = check_reachability(&level);
+        assert_eq!(
+            issues,
+            vec![
+                LevelIssue::warning(IssueKind::UnreachableArea { size: 16 }, vec3(6., 1., 0.)),
+                LevelIssue::warning(
+                    IssueKind::UnreachableCreature("Lost pizza".into()),
+                    vec3(7.5, 1., 3.5),
+                ),
+            ]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+pub mod archetype;
+pub mod damage;
+pub mod item;
 pub mod level;
 pub mod voxel;
+pub mod weapon;
 
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum WorldSide {
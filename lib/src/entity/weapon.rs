@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::archetype::ProjectileParams;
+use crate::entity::damage::DamageType;
+use crate::util::json_dir::read_named_json_dir;
+
+/// Weapon the player can equip, one JSON file per weapon.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WeaponParams {
+    pub name: String,
+    /// Scene attached to the hand of the player, nothing is shown if not set
+    #[serde(default)]
+    pub mesh: Option<String>,
+    /// Replaces the thrust of bare hands
+    #[serde(default)]
+    pub melee: Option<MeleeParams>,
+    /// The player can't shoot without it
+    #[serde(default)]
+    pub ranged: Option<RangedParams>,
+    /// One is played for every hit, the punch or the bullet sounds if empty
+    #[serde(default)]
+    pub hit_sounds: Vec<String>,
+    #[serde(default)]
+    pub hit_effect: HitEffect,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MeleeParams {
+    pub reach: f32,
+    pub damage: u16,
+    pub damage_type: DamageType,
+    /// From 0 to 1
+    pub crit_chance: f32,
+    /// Seconds between the strikes
+    pub cooldown: f32,
+}
+
+impl Default for MeleeParams {
+    fn default() -> Self {
+        MeleeParams {
+            reach: 1.5,
+            damage: 10,
+            damage_type: DamageType::Physical,
+            crit_chance: 0.1,
+            cooldown: 0.3,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RangedParams {
+    pub damage: u16,
+    pub damage_type: DamageType,
+    /// From 0 to 1
+    pub crit_chance: f32,
+    /// Distance per second
+    pub projectile_speed: f32,
+    pub projectile: ProjectileParams,
+    pub magazine: u16,
+    /// Seconds between the shots
+    pub cooldown: f32,
+    /// Seconds
+    pub reload_time: f32,
+}
+
+impl Default for RangedParams {
+    fn default() -> Self {
+        RangedParams {
+            damage: 6,
+            damage_type: DamageType::Physical,
+            crit_chance: 0.1,
+            projectile_speed: 20.0,
+            projectile: ProjectileParams {
+                max_range: 30.0,
+                ..Default::default()
+            },
+            magazine: 8,
+            cooldown: 0.25,
+            reload_time: 1.5,
+        }
+    }
+}
+
+/// Particles shown on the hit target.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HitEffect {
+    #[default]
+    Punch,
+    Sparks,
+}
+
+/// Index of the weapon in `Weapons`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeaponId(usize);
+
+#[derive(Debug, Default)]
+pub struct Weapons {
+    weapons: Vec<WeaponParams>,
+}
+
+impl Weapons {
+    /// Weapons of the dir in the order of the file names, which the ids follow.
+    pub fn from_dir(path: &str) -> Weapons {
+        let weapons = read_named_json_dir(path, "weapon", |weapon: &WeaponParams| &weapon.name);
+        Weapons { weapons }
+    }
+
+    pub fn get(&self, id: WeaponId) -> &WeaponParams {
+        &self.weapons[id.0]
+    }
+
+    pub fn find(&self, name: &str) -> Option<WeaponId> {
+        self.weapons
+            .iter()
+            .position(|weapon| weapon.name == name)
+            .map(WeaponId)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (WeaponId, &WeaponParams)> {
+        self.weapons
+            .iter()
+            .enumerate()
+            .map(|(i, weapon)| (WeaponId(i), weapon))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_weapons_are_valid() {
+        let weapons = Weapons::from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/weapon"));
+
+        let sword = weapons.find("Sword").unwrap();
+        assert!(weapons.get(sword).melee.is_some());
+        let pistol = weapons.find("Pistol").unwrap();
+        assert_eq!(weapons.get(pistol).hit_effect, HitEffect::Sparks);
+    }
+}
@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 pub struct DebugSettings {
     pub fly_camera: bool,
     pub debug_render: bool,
+    /// Window with the behaviour state of every creature
+    #[serde(default)]
+    pub creature_states: bool,
 }
 
 impl DebugSettings {
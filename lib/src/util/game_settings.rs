@@ -7,6 +7,12 @@ use serde::{Deserialize, Serialize};
 pub struct GameSettings {
     background_music_volume: Option<f64>,
     mouse_sensitivity: Option<f64>,
+    /// Creatures further from the player think less often
+    creature_active_distance: Option<f32>,
+    /// Creatures further from the player sleep until something wakes them
+    creature_dormant_distance: Option<f32>,
+    /// Rapid hits on one target show as one growing number
+    merge_damage_numbers: Option<bool>,
 }
 
 impl GameSettings {
@@ -36,6 +42,18 @@ impl GameSettings {
         self.mouse_sensitivity = Some(new_value);
     }
 
+    pub fn get_creature_active_distance(&self) -> f32 {
+        self.creature_active_distance.unwrap_or(40.0)
+    }
+
+    pub fn get_creature_dormant_distance(&self) -> f32 {
+        self.creature_dormant_distance.unwrap_or(80.0)
+    }
+
+    pub fn get_merge_damage_numbers(&self) -> bool {
+        self.merge_damage_numbers.unwrap_or(true)
+    }
+
     pub fn save(&self) {
         let serialized = serde_json::to_string(self).unwrap();
 
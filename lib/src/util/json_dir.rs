@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+
+/// Parses every `*.json` of the dir sorted by the file name, `kind` names them in the errors.
+pub fn read_json_dir<T: DeserializeOwned>(path: &str, kind: &str) -> Vec<T> {
+    let files =
+        fs::read_dir(path).unwrap_or_else(|e| panic!("Can't read {kind}s from {path}: {e}"));
+    let mut paths: Vec<PathBuf> = files
+        .flatten()
+        .map(|file| file.path())
+        .filter(|file_path| {
+            file_path
+                .extension()
+                .map_or(false, |extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|file_path| {
+            let mut json = String::new();
+            File::open(file_path)
+                .and_then(|mut file| file.read_to_string(&mut json))
+                .unwrap_or_else(|e| panic!("Can't read {kind} {file_path:?}: {e}"));
+            serde_json::from_str(&json)
+                .unwrap_or_else(|e| panic!("Failed to parse {kind} {file_path:?}: {e}"))
+        })
+        .collect()
+}
+
+/// As `read_json_dir`, but no two of them may share the `name`.
+pub fn read_named_json_dir<T: DeserializeOwned>(
+    path: &str,
+    kind: &str,
+    name: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    let values: Vec<T> = read_json_dir(path, kind);
+
+    let mut names = HashSet::new();
+    for value in &values {
+        if !names.insert(name(value)) {
+            panic!("The {kind} {} is defined twice", name(value));
+        }
+    }
+
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::env;
+
+    #[derive(Deserialize)]
+    struct Named {
+        name: String,
+    }
+
+    fn dir_with(dir_name: &str, files: &[(&str, &str)]) -> String {
+        let dir = env::temp_dir().join(dir_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (file_name, content) in files {
+            fs::write(dir.join(file_name), content).unwrap();
+        }
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn only_json_files_are_read_by_name() {
+        let dir = dir_with(
+            "lib_json_dir_read",
+            &[
+                ("b.json", r#"{ "name": "first" }"#),
+                ("a.json", r#"{ "name": "second" }"#),
+                ("notes.txt", "not json"),
+            ],
+        );
+
+        let values: Vec<Named> = read_named_json_dir(&dir, "thing", |n: &Named| &n.name);
+        let names: Vec<&str> = values.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["second", "first"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "The thing twin is defined twice")]
+    fn names_must_be_unique() {
+        let dir = dir_with(
+            "lib_json_dir_twice",
+            &[
+                ("a.json", r#"{ "name": "twin" }"#),
+                ("b.json", r#"{ "name": "twin" }"#),
+            ],
+        );
+
+        read_named_json_dir(&dir, "thing", |n: &Named| &n.name);
+    }
+}
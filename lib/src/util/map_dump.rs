@@ -0,0 +1,262 @@
+use std::io::Write;
+
+use crate::entity::level::creature::CreatureName;
+use crate::entity::level::Level;
+use crate::entity::voxel::Material;
+
+const EMPTY_COLOR: [u8; 3] = [20, 20, 30];
+const LIGHT_COLOR: [u8; 3] = [255, 230, 0];
+const ENEMY_COLOR: [u8; 3] = [230, 0, 0];
+const CREATURE_COLOR: [u8; 3] = [0, 200, 0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MapProjection {
+    /// The highest voxel of every column.
+    TopDown,
+    /// Only the voxels of one plate.
+    Slice(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MapCell {
+    material: Material,
+    height: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Marker {
+    Light,
+    Creature { name: CreatureName, enemy: bool },
+}
+
+/// CPU-only picture of a level, the same level always produces the same map.
+pub struct LevelMap {
+    width: usize,
+    depth: usize,
+    max_height: usize,
+    cells: Vec<Option<MapCell>>,
+    markers: Vec<(usize, usize, Marker)>,
+}
+
+impl LevelMap {
+    pub fn new(level: &Level, projection: MapProjection) -> LevelMap {
+        let plates = level.voxel_stack().plates();
+        let mut width = 0;
+        let mut depth = 0;
+        for (_, plate) in &plates {
+            for (z, row) in plate.rows() {
+                depth = depth.max(z + 1);
+                for voxel in row {
+                    width = width.max(voxel.position.x as usize + 1);
+                }
+            }
+        }
+
+        let mut map = LevelMap {
+            width,
+            depth,
+            max_height: plates.last().map_or(0, |(y, _)| *y),
+            cells: vec![None; width * depth],
+            markers: vec![],
+        };
+
+        // plates are sorted from the bottom, so the highest voxel wins
+        for (y, plate) in plates {
+            if projection != MapProjection::TopDown && projection != MapProjection::Slice(y) {
+                continue;
+            }
+            for (z, row) in plate.rows() {
+                for voxel in row {
+                    let index = z * width + voxel.position.x as usize;
+                    map.cells[index] = Some(MapCell {
+                        material: voxel.material,
+                        height: y,
+                    });
+                }
+            }
+        }
+
+        for light in level.lights() {
+            let y = light.position.y as usize;
+            if contains_height(projection, y, 1) {
+                map.add_marker(light.position.x, light.position.z, Marker::Light);
+            }
+        }
+        for creature in level.creatures() {
+            let y = creature.position.y as usize;
+            if contains_height(projection, y, 2) {
+                let marker = Marker::Creature {
+                    name: creature.name.clone(),
+                    enemy: creature.is_enemy(),
+                };
+                map.add_marker(creature.position.x, creature.position.z, marker);
+            }
+        }
+
+        map
+    }
+
+    /// One char per voxel column, rows go along the z axis.
+    pub fn to_ascii(&self) -> String {
+        let mut result = String::with_capacity((self.width + 1) * self.depth);
+
+        for z in 0..self.depth {
+            for x in 0..self.width {
+                let symbol = match self.marker(x, z) {
+                    Some(Marker::Light) => '*',
+                    Some(Marker::Creature { name, .. }) => creature_symbol(name),
+                    None => {
+                        self.cells[z * self.width + x].map_or(' ', |c| material_symbol(c.material))
+                    }
+                };
+                result.push(symbol);
+            }
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// Writes the map as an RGB PNG, every voxel column is a `scale`x`scale` square.
+    pub fn write_png(&self, writer: impl Write, scale: u32) -> Result<(), png::EncodingError> {
+        let scale = scale.max(1) as usize;
+        let image_width = self.width * scale;
+        let image_height = self.depth * scale;
+        let mut data = vec![0; image_width * image_height * 3];
+
+        for z in 0..self.depth {
+            for x in 0..self.width {
+                let color = match self.marker(x, z) {
+                    Some(Marker::Light) => LIGHT_COLOR,
+                    Some(Marker::Creature { enemy: true, .. }) => ENEMY_COLOR,
+                    Some(Marker::Creature { enemy: false, .. }) => CREATURE_COLOR,
+                    None => self.cells[z * self.width + x].map_or(EMPTY_COLOR, |cell| {
+                        shade(material_color(cell.material), cell.height, self.max_height)
+                    }),
+                };
+
+                for pixel_z in z * scale..(z + 1) * scale {
+                    for pixel_x in x * scale..(x + 1) * scale {
+                        let offset = (pixel_z * image_width + pixel_x) * 3;
+                        data[offset..offset + 3].copy_from_slice(&color);
+                    }
+                }
+            }
+        }
+
+        let mut encoder = png::Encoder::new(writer, image_width as u32, image_height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut png_writer = encoder.write_header()?;
+        png_writer.write_image_data(&data)
+    }
+
+    fn add_marker(&mut self, x: f32, z: f32, marker: Marker) {
+        if x >= 0. && z >= 0. && (x as usize) < self.width && (z as usize) < self.depth {
+            self.markers.push((x as usize, z as usize, marker));
+        }
+    }
+
+    fn marker(&self, x: usize, z: usize) -> Option<&Marker> {
+        self.markers
+            .iter()
+            .rev()
+            .find(|(marker_x, marker_z, _)| *marker_x == x && *marker_z == z)
+            .map(|(.., marker)| marker)
+    }
+}
+
+fn contains_height(projection: MapProjection, y: usize, height: usize) -> bool {
+    match projection {
+        MapProjection::TopDown => true,
+        MapProjection::Slice(slice) => (y..y + height).contains(&slice),
+    }
+}
+
+fn material_symbol(material: Material) -> char {
+    match material {
+        Material::Solid => '#',
+        Material::Passable => '%',
+        Material::Water => '~',
+        Material::OrangeLight | Material::BlueLight => '*',
+        Material::Unknown => '?',
+    }
+}
+
+fn creature_symbol(name: &CreatureName) -> char {
+    name.0
+        .chars()
+        .next()
+        .map_or('?', |symbol| symbol.to_ascii_uppercase())
+}
+
+fn material_color(material: Material) -> [u8; 3] {
+    match material {
+        Material::Solid => [150, 150, 150],
+        Material::Passable => [40, 140, 40],
+        Material::Water => [40, 80, 220],
+        Material::OrangeLight => [255, 140, 0],
+        Material::BlueLight => [60, 160, 255],
+        Material::Unknown => [255, 0, 255],
+    }
+}
+
+// Lower columns are darker, so the relief is visible on the top-down picture
+fn shade(color: [u8; 3], height: usize, max_height: usize) -> [u8; 3] {
+    let brightness = 0.4 + 0.6 * height as f32 / max_height.max(1) as f32;
+
+    color.map(|channel| (f32::from(channel) * brightness) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::level::creature::Creature;
+    use crate::entity::level::DayPart;
+    use crate::entity::voxel::{Shape, Voxel};
+    use bevy::math::vec3;
+
+    fn level() -> Level {
+        let mut voxels = vec![];
+        for x in 0..4 {
+            for z in 0..3 {
+                voxels.push(Voxel::new(
+                    vec3(x as f32, 0., z as f32),
+                    Material::Solid,
+                    Shape::Cube,
+                ));
+            }
+        }
+        voxels.push(Voxel::new(vec3(0., 1., 0.), Material::Water, Shape::Cube));
+        voxels.push(Voxel::new(
+            vec3(3., 1., 2.),
+            Material::OrangeLight,
+            Shape::Cube,
+        ));
+        voxels.push(Voxel::new(
+            vec3(1., 2., 1.),
+            Material::Passable,
+            Shape::Cube,
+        ));
+        let creatures = vec![Creature::enemy(
+            CreatureName::from("Pizza"),
+            vec3(2., 1., 0.),
+        )];
+
+        Level::new("golden".to_string(), voxels, DayPart::Day, creatures)
+    }
+
+    #[test]
+    fn top_down_ascii() {
+        let map = LevelMap::new(&level(), MapProjection::TopDown);
+
+        assert_eq!(map.to_ascii(), "~#P#\n#%##\n###*\n");
+    }
+
+    #[test]
+    fn slice_ascii() {
+        let map = LevelMap::new(&level(), MapProjection::Slice(1));
+
+        assert_eq!(map.to_ascii(), "~ P \n    \n   *\n");
+    }
+}
@@ -1,5 +1,63 @@
+use bevy::math::{vec3, IVec3, Vec3};
+
+/// Voxel the position is in, the voxel `n` spans from `n` to `n + 1` like its collider.
+pub fn voxel_at(position: Vec3) -> IVec3 {
+    position.floor().as_ivec3()
+}
+
+/// Middle of the bottom of the voxel, where a body standing in it is centered.
+pub fn voxel_center(point: IVec3) -> Vec3 {
+    point.as_vec3() + vec3(0.5, 0., 0.5)
+}
+
 pub fn round_based(n: f32, base: u32) -> f32 {
     let modifier = 10_i32.pow(base) as f32;
 
     (n * modifier).round() / modifier
 }
+
+/// Voxel points touched by a cylinder body with the given radius standing at `x`, `z`.
+pub fn touched_points(x: f32, y: f32, z: f32, radius: f32) -> Vec<Vec3> {
+    let mut points: Vec<Vec3> = vec![vec3(x.floor(), y, z), vec3(x, y, z.floor())];
+
+    let x_gap = round_based(x - x.floor(), 1);
+    if x_gap > radius {
+        points.push(vec3((x + radius).floor(), y, z.floor()));
+    } else if x_gap < radius {
+        points.push(vec3((x - radius).floor(), y, z.floor()));
+    };
+
+    let z_gap = round_based(z - z.floor(), 1);
+    if z_gap > radius {
+        points.push(vec3(x.floor(), y, (z + radius).floor()));
+    } else if z_gap < radius {
+        points.push(vec3(x.floor(), y, (z - radius).floor()));
+    };
+
+    if x_gap > radius && z_gap > radius {
+        points.push(vec3((x + radius).floor(), y, (z + radius).floor()));
+    } else if x_gap < radius && z_gap < radius {
+        points.push(vec3((x - radius).floor(), y, (z - radius).floor()));
+    } else if x_gap > radius && z_gap < radius {
+        points.push(vec3((x + radius).floor(), y, (z - radius).floor()));
+    } else if x_gap < radius && z_gap > radius {
+        points.push(vec3((x - radius).floor(), y, (z + radius).floor()));
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::ivec3;
+
+    #[test]
+    fn positions_map_to_voxels_by_floor() {
+        assert_eq!(voxel_at(vec3(2.9, 1.0, 0.1)), ivec3(2, 1, 0));
+        assert_eq!(voxel_at(vec3(-0.2, 0.5, 3.5)), ivec3(-1, 0, 3));
+        let center = voxel_center(ivec3(4, 2, 7));
+        assert_eq!(center, vec3(4.5, 2., 7.5));
+        assert_eq!(voxel_at(center), ivec3(4, 2, 7));
+    }
+}
@@ -1,3 +1,6 @@
 pub mod debug_settings;
 pub mod game_settings;
+pub mod json_dir;
+pub mod map_dump;
 pub mod math;
+pub mod save;
@@ -0,0 +1,112 @@
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{ErrorKind, Read, Write};
+
+use bevy::log::{error, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::item::ItemStack;
+
+/// Progress of the player kept between the sessions.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct SaveGame {
+    pub inventory: Vec<ItemStack>,
+}
+
+impl SaveGame {
+    /// A new game if there is no save yet or it's broken.
+    pub fn from_file(path: &str) -> SaveGame {
+        match SaveGame::read(path) {
+            Ok(save) => save,
+            Err(e) if e.kind() == ErrorKind::NotFound => SaveGame::default(),
+            Err(e) => {
+                warn!("Can't load save file {path}, starting a new game: {e}");
+                SaveGame::default()
+            }
+        }
+    }
+
+    /// A failed save only loses the progress since the last one, the game goes on.
+    pub fn save(&self, path: &str) {
+        if let Err(e) = self.write(path) {
+            error!("Can't write save file {path}: {e}");
+        }
+    }
+
+    fn read(path: &str) -> io::Result<SaveGame> {
+        let mut json = String::new();
+        File::open(path)?.read_to_string(&mut json)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// The old save is replaced only once the new one is written in full.
+    fn write(&self, path: &str) -> io::Result<()> {
+        let serialized = serde_json::to_string(self)?;
+        let temp_path = format!("{path}.tmp");
+        let mut file = File::create(&temp_path)?;
+        file.write_all(serialized.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(temp_path, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::Path;
+
+    #[test]
+    fn save_survives_round_trip() {
+        let path = env::temp_dir().join("lib_save_round_trip.json");
+        let path = path.to_str().unwrap();
+        let save = SaveGame {
+            inventory: vec![ItemStack {
+                item: "pizza_slice".to_string(),
+                count: 3,
+            }],
+        };
+
+        save.save(path);
+        assert_eq!(SaveGame::from_file(path), save);
+        assert!(!Path::new(&format!("{path}.tmp")).exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn failed_save_keeps_the_old_one() {
+        let dir = env::temp_dir().join("lib_save_failed");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("save.json");
+        let path = path.to_str().unwrap();
+        let old = SaveGame {
+            inventory: vec![ItemStack {
+                item: "bone".to_string(),
+                count: 1,
+            }],
+        };
+        old.save(path);
+        // The temporary file can't be created where a dir is
+        fs::create_dir_all(format!("{path}.tmp")).unwrap();
+
+        SaveGame::default().save(path);
+        assert_eq!(SaveGame::from_file(path), old);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn broken_save_starts_new_game() {
+        let path = env::temp_dir().join("lib_save_broken.json");
+        let path = path.to_str().unwrap();
+        fs::write(path, "{ \"inventory\": [").unwrap();
+
+        assert_eq!(SaveGame::from_file(path), SaveGame::default());
+        assert_eq!(
+            SaveGame::from_file(&format!("{path}.missing")),
+            SaveGame::default()
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
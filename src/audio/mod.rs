@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_kira_audio::AudioSource;
 use bevy_kira_audio::{Audio, AudioControl};
+use lib::entity::weapon::{WeaponId, Weapons};
 use lib::util::game_settings::GameSettings;
 use rand::Rng;
 use std::collections::HashMap;
@@ -14,15 +15,23 @@ pub struct SoundsAssets {
 pub struct SoundEvent {
     pub sound_layer: SoundLayer,
     pub sound_type: SoundType,
+    /// Where creatures can hear it, `None` for sounds outside of the world
+    pub position: Option<Vec3>,
+    /// The attacker or the shooter, creatures only go after the noises of hostiles
+    pub source: Option<Entity>,
 }
 #[derive(Copy, Clone)]
 pub enum SoundType {
     Damage(DamageSoundType),
 }
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum DamageSoundType {
     Punch,
     Bullet,
+    /// Projectile hitting the level
+    Wall,
+    /// Hit by the weapon of the player
+    Weapon(WeaponId),
 }
 #[derive(Copy, Clone)]
 pub enum SoundLayer {
@@ -42,7 +51,11 @@ impl Plugin for GameAudioPlugin {
     }
 }
 
-fn audio_setup_sound_assets(asset_server: Res<AssetServer>, mut commands: Commands) {
+fn audio_setup_sound_assets(
+    asset_server: Res<AssetServer>,
+    weapons: Res<Weapons>,
+    mut commands: Commands,
+) {
     let load = |path: &str| -> Handle<AudioSource> { asset_server.load(path) };
     let mut damage = HashMap::new();
 
@@ -64,6 +77,23 @@ fn audio_setup_sound_assets(asset_server: Res<AssetServer>, mut commands: Comman
             load("audio/foreground/damage/bullet/body_1.mp3"),
         ],
     );
+    damage.insert(
+        DamageSoundType::Wall,
+        vec![load("audio/foreground/damage/bullet/wall_0.mp3")],
+    );
+    for (id, weapon) in weapons.iter() {
+        let sounds = if weapon.hit_sounds.is_empty() {
+            let fallback = if weapon.melee.is_some() {
+                DamageSoundType::Punch
+            } else {
+                DamageSoundType::Bullet
+            };
+            damage[&fallback].clone()
+        } else {
+            weapon.hit_sounds.iter().map(|path| load(path)).collect()
+        };
+        damage.insert(DamageSoundType::Weapon(id), sounds);
+    }
 
     commands.insert_resource(SoundsAssets {
         damage,
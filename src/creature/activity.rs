@@ -0,0 +1,207 @@
+use crate::creature::component::attack::event::DamageEvent;
+use crate::creature::component::perception::Perception;
+use crate::creature::CreatureMarker;
+use crate::player::PlayerMarker;
+use crate::GameState;
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy::math::vec3;
+use bevy::prelude::*;
+use heron::Velocity;
+use lib::util::game_settings::GameSettings;
+use std::time::{Duration, Instant};
+
+pub const ACTIVE_CREATURES: DiagnosticId =
+    DiagnosticId::from_u128(0x6d6b_1c2e_8f4a_4b53_9a1d_3e70_c5a2_0001);
+pub const REDUCED_CREATURES: DiagnosticId =
+    DiagnosticId::from_u128(0x6d6b_1c2e_8f4a_4b53_9a1d_3e70_c5a2_0002);
+pub const DORMANT_CREATURES: DiagnosticId =
+    DiagnosticId::from_u128(0x6d6b_1c2e_8f4a_4b53_9a1d_3e70_c5a2_0003);
+/// Creatures whose AI ran in the frame
+pub const TICKED_CREATURES: DiagnosticId =
+    DiagnosticId::from_u128(0x6d6b_1c2e_8f4a_4b53_9a1d_3e70_c5a2_0004);
+
+// Creatures far from the player think once in this many frames
+const REDUCED_TICK_INTERVAL: u32 = 4;
+// A hit creature stays awake this long however far the player is
+const WAKE_TIME: Duration = Duration::from_secs(10);
+
+pub struct ActivityPlugin;
+
+impl Plugin for ActivityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(activity_setup_diagnostics)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(activity_update_levels)
+                    .with_system(activity_wake_damaged),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityLevel {
+    Active,
+    /// Thinks every few frames, keeps moving the way it chose in between
+    Reduced,
+    /// Stands still until the player comes closer, it hears a noise or gets hit
+    Dormant,
+}
+
+/// How often the AI of the creature runs.
+#[derive(Component, Debug)]
+pub struct Activity {
+    level: ActivityLevel,
+    frame: u32,
+    woken_at: Option<Instant>,
+}
+
+impl Activity {
+    /// `seed` spreads the ticks of reduced creatures over the frames.
+    pub fn new(seed: u32) -> Self {
+        Activity {
+            level: ActivityLevel::Active,
+            frame: seed,
+            woken_at: None,
+        }
+    }
+
+    pub fn level(&self) -> ActivityLevel {
+        self.level
+    }
+
+    /// The AI of the creature runs in this frame.
+    pub fn is_ticking(&self) -> bool {
+        match self.level {
+            ActivityLevel::Active => true,
+            ActivityLevel::Reduced => self.frame % REDUCED_TICK_INTERVAL == 0,
+            ActivityLevel::Dormant => false,
+        }
+    }
+
+    pub fn wake(&mut self) {
+        self.woken_at = Some(Instant::now());
+    }
+
+    /// A creature which knows about a target stays active wherever the player is.
+    pub fn update(&mut self, distance: f32, knows_target: bool, settings: &GameSettings) {
+        self.frame = self.frame.wrapping_add(1);
+        let is_awake = knows_target
+            || self
+                .woken_at
+                .map_or(false, |woken_at| woken_at.elapsed() < WAKE_TIME);
+
+        self.level = if is_awake || distance <= settings.get_creature_active_distance() {
+            ActivityLevel::Active
+        } else if distance <= settings.get_creature_dormant_distance() {
+            ActivityLevel::Reduced
+        } else {
+            ActivityLevel::Dormant
+        };
+    }
+}
+
+fn activity_setup_diagnostics(mut diagnostics: ResMut<Diagnostics>) {
+    diagnostics.add(Diagnostic::new(ACTIVE_CREATURES, "active_creatures", 20));
+    diagnostics.add(Diagnostic::new(REDUCED_CREATURES, "reduced_creatures", 20));
+    diagnostics.add(Diagnostic::new(DORMANT_CREATURES, "dormant_creatures", 20));
+    diagnostics.add(Diagnostic::new(TICKED_CREATURES, "ticked_creatures", 20));
+}
+
+fn activity_update_levels(
+    settings: Res<GameSettings>,
+    mut diagnostics: ResMut<Diagnostics>,
+    player: Query<&Transform, With<PlayerMarker>>,
+    mut creatures: Query<
+        (&Transform, &Perception, &mut Activity, &mut Velocity),
+        With<CreatureMarker>,
+    >,
+) {
+    let player = if let Ok(transform) = player.get_single() {
+        transform.translation
+    } else {
+        return;
+    };
+
+    let (mut active, mut reduced, mut dormant, mut ticked) = (0, 0, 0, 0);
+    for (transform, perception, mut activity, mut velocity) in creatures.iter_mut() {
+        let previous = activity.level();
+        let distance = transform.translation.distance(player);
+        activity.update(distance, perception.memory().is_some(), &settings);
+
+        match activity.level() {
+            ActivityLevel::Active => active += 1,
+            ActivityLevel::Reduced => reduced += 1,
+            ActivityLevel::Dormant => dormant += 1,
+        }
+        if activity.is_ticking() {
+            ticked += 1;
+        }
+        if activity.level() == ActivityLevel::Dormant && previous != ActivityLevel::Dormant {
+            velocity.linear = vec3(0., velocity.linear.y, 0.);
+        }
+    }
+
+    diagnostics.add_measurement(ACTIVE_CREATURES, f64::from(active));
+    diagnostics.add_measurement(REDUCED_CREATURES, f64::from(reduced));
+    diagnostics.add_measurement(DORMANT_CREATURES, f64::from(dormant));
+    diagnostics.add_measurement(TICKED_CREATURES, f64::from(ticked));
+}
+
+fn activity_wake_damaged(
+    mut damage_events: EventReader<DamageEvent>,
+    mut creatures: Query<&mut Activity>,
+) {
+    for event in damage_events.iter() {
+        if let Ok(mut activity) = creatures.get_mut(event.target) {
+            activity.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> GameSettings {
+        serde_json::from_str(
+            r#"{ "creature_active_distance": 10.0, "creature_dormant_distance": 20.0 }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn far_creatures_tick_less() {
+        let settings = settings();
+        let mut activity = Activity::new(0);
+
+        activity.update(5., false, &settings);
+        assert_eq!(activity.level(), ActivityLevel::Active);
+        assert!(activity.is_ticking());
+
+        let ticks = (0..REDUCED_TICK_INTERVAL * 3)
+            .filter(|_| {
+                activity.update(15., false, &settings);
+                activity.is_ticking()
+            })
+            .count();
+        assert_eq!(activity.level(), ActivityLevel::Reduced);
+        assert_eq!(ticks, 3);
+
+        activity.update(30., false, &settings);
+        assert_eq!(activity.level(), ActivityLevel::Dormant);
+        assert!(!activity.is_ticking());
+    }
+
+    #[test]
+    fn alerted_creatures_wake_up() {
+        let settings = settings();
+        let mut activity = Activity::new(0);
+
+        activity.update(30., true, &settings);
+        assert_eq!(activity.level(), ActivityLevel::Active);
+
+        activity.wake();
+        activity.update(30., false, &settings);
+        assert_eq!(activity.level(), ActivityLevel::Active);
+    }
+}
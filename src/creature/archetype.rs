@@ -0,0 +1,87 @@
+use crate::creature::activity::Activity;
+use crate::creature::boss::Boss;
+use crate::creature::buffs::BuffStorage;
+use crate::creature::component::attack::component::AttackSet;
+use crate::creature::component::attack::damage::Resistance;
+use crate::creature::component::behaviour::{Behaviour, BehaviourConfig};
+use crate::creature::component::hp::HP;
+use crate::creature::component::movement::chase_movement::ChaseConfig;
+use crate::creature::component::movement::MovementStrategy;
+use crate::creature::component::perception::{Perception, PerceptionConfig};
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::ecs::system::EntityCommands;
+use heron::Velocity;
+use lib::entity::archetype::{BehaviourParams, CreatureArchetype, MovementKind};
+use std::time::Duration;
+
+/// Inserts the components described by the archetype, `patrol` replaces its idle movement.
+pub fn insert(
+    entity_commands: &mut EntityCommands,
+    archetype: &CreatureArchetype,
+    patrol: Option<MovementStrategy>,
+) {
+    let perception = &archetype.perception;
+    let idle = patrol.unwrap_or_else(|| match archetype.movement {
+        MovementKind::Stand => MovementStrategy::stand(),
+        MovementKind::Random => MovementStrategy::random(),
+    });
+
+    let params = &archetype.physiology;
+    let physiology = PhysiologyDescription {
+        model_height: params.model_height,
+        model_radius: params.model_radius,
+        jump_height: params.jump_height,
+        movement_speed: params.movement_speed,
+        eyes_height: params.eyes_height.unwrap_or(params.model_height / 2.),
+    };
+
+    let seed = entity_commands.id().id();
+    entity_commands
+        .insert(physiology.collision_shape())
+        .insert(physiology)
+        .insert(Velocity::default())
+        .insert(Activity::new(seed))
+        .insert(Perception::new(PerceptionConfig {
+            sight_range: perception.sight_range,
+            dark_sight_range: perception.dark_sight_range,
+            field_of_view: perception.field_of_view.to_radians(),
+            hearing_range: perception.hearing_range,
+            memory: Duration::from_secs_f32(perception.memory.max(0.)),
+        }))
+        .insert(idle)
+        .insert(HP::full(archetype.hp))
+        .insert(Resistance(archetype.resistances.clone()));
+
+    if let Some(behaviour) = &archetype.behaviour {
+        let (config, chase, flee) = behaviour_parts(behaviour);
+        entity_commands.insert(Behaviour::new(config, chase, flee));
+    }
+
+    if !archetype.attacks.is_empty() {
+        entity_commands.insert(AttackSet::new(archetype.attacks.clone()));
+    }
+
+    if let Some(boss) = &archetype.boss {
+        entity_commands
+            .insert(Boss::new(boss.clone()))
+            .insert(BuffStorage::<PhysiologyDescription>::new());
+    }
+}
+
+/// Config with the chase and flee strategies of the behaviour.
+pub fn behaviour_parts(
+    behaviour: &BehaviourParams,
+) -> (BehaviourConfig, MovementStrategy, MovementStrategy) {
+    let config = BehaviourConfig {
+        attack_range: behaviour.attack_range,
+        reaction_time: Duration::from_secs_f32(behaviour.reaction_time.max(0.)),
+        alert_duration: Duration::from_secs_f32(behaviour.alert_duration.max(0.)),
+        flee_below_hp: behaviour.flee_below_hp,
+    };
+    let chase = MovementStrategy::chase(ChaseConfig {
+        preferred_distance: behaviour.preferred_distance,
+    });
+    let flee = MovementStrategy::flee(behaviour.flee_distance);
+
+    (config, chase, flee)
+}
@@ -0,0 +1,129 @@
+use crate::creature::archetype::behaviour_parts;
+use crate::creature::buffs::speed::SpeedBuff;
+use crate::creature::buffs::{BuffClock, BuffStorage};
+use crate::creature::component::attack::component::AttackSet;
+use crate::creature::component::behaviour::Behaviour;
+use crate::creature::component::hp::HP;
+use crate::creature::component::movement::MovementStrategy;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use crate::GameState;
+use bevy::prelude::*;
+use lib::entity::archetype::{BossParams, BossPhaseParams};
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BossPhaseChanged>().add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(boss_switch_phases)
+                .with_system(boss_apply_phase_buffs),
+        );
+    }
+}
+
+/// Sent when the boss enters a phase, the first one too, for the music and the effects.
+pub struct BossPhaseChanged {
+    pub boss: Entity,
+    pub phase: usize,
+    pub position: Vec3,
+}
+
+/// Creature fighting by phases, which change with its HP.
+#[derive(Component, Debug)]
+pub struct Boss {
+    params: BossParams,
+    phase: Option<usize>,
+}
+
+impl Boss {
+    pub fn new(params: BossParams) -> Self {
+        Boss {
+            params,
+            phase: None,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.params.title
+    }
+
+    pub fn phase(&self) -> Option<usize> {
+        self.phase
+    }
+
+    fn current_phase(&self) -> Option<&BossPhaseParams> {
+        self.phase.and_then(|phase| self.params.phases.get(phase))
+    }
+
+    /// The next phase once HP falls to its threshold, phases never go back.
+    fn next_phase(&self, hp: f32) -> Option<usize> {
+        let phase = self.params.phase_at(hp)?;
+        self.phase
+            .map_or(true, |current| phase > current)
+            .then_some(phase)
+    }
+}
+
+fn boss_switch_phases(
+    mut commands: Commands,
+    mut phase_events: EventWriter<BossPhaseChanged>,
+    mut bosses: Query<(
+        Entity,
+        &HP,
+        &Transform,
+        &mut Boss,
+        &mut MovementStrategy,
+        Option<&mut Behaviour>,
+        Option<&mut AttackSet>,
+    )>,
+) {
+    for (entity, hp, transform, mut boss, mut movement, behaviour, attacks) in bosses.iter_mut() {
+        let phase = match boss.next_phase(hp.percent()) {
+            Some(phase) if !hp.is_empty() => phase,
+            _ => continue,
+        };
+        boss.phase = Some(phase);
+        let params = &boss.params.phases[phase];
+
+        if let Some(behaviour_params) = &params.behaviour {
+            let (config, chase, flee) = behaviour_parts(behaviour_params);
+            if let Some(mut behaviour) = behaviour {
+                behaviour.reconfigure(config, chase, flee, &mut movement);
+            } else {
+                commands
+                    .entity(entity)
+                    .insert(Behaviour::new(config, chase, flee));
+            }
+        }
+
+        if let Some(attack_params) = &params.attacks {
+            let attack_set = AttackSet::new(attack_params.clone());
+            if let Some(mut attacks) = attacks {
+                *attacks = attack_set;
+            } else {
+                commands.entity(entity).insert(attack_set);
+            }
+        }
+
+        info!("Boss {} enters phase {}", boss.title(), phase + 1);
+        phase_events.send(BossPhaseChanged {
+            boss: entity,
+            phase,
+            position: transform.translation,
+        });
+    }
+}
+
+fn boss_apply_phase_buffs(mut bosses: Query<(&Boss, &mut BuffStorage<PhysiologyDescription>)>) {
+    for (boss, mut buffs) in bosses.iter_mut() {
+        if let Some(phase) = boss.current_phase() {
+            if (phase.speed_multiplier - 1.).abs() > f32::EPSILON {
+                buffs.add(BuffClock::frame(
+                    Box::new(SpeedBuff::new(phase.speed_multiplier)),
+                    1,
+                ));
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::creature::buffs::sprint::{buffs_add_sprint};
 use crate::creature::buffs::system::{apply_buffs, clear_buffs};
 use crate::creature::component::physiology_description::PhysiologyDescription;
 
+pub mod speed;
 mod sprint;
 mod system;
 
@@ -0,0 +1,22 @@
+use crate::creature::buffs::Buff;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+
+#[derive(Debug)]
+pub struct SpeedBuff {
+    speed_multiplier: f32
+}
+
+impl SpeedBuff {
+    pub fn new(speed_multiplier: f32) -> SpeedBuff {
+        SpeedBuff { speed_multiplier }
+    }
+}
+
+impl Buff<PhysiologyDescription> for SpeedBuff {
+    fn apply(&self, phys: &mut PhysiologyDescription) {
+        phys.movement_speed *= self.speed_multiplier;
+    }
+    fn remove(&self, phys: &mut PhysiologyDescription) {
+        phys.movement_speed /= self.speed_multiplier;
+    }
+}
@@ -1,6 +1,5 @@
 use bevy::prelude::*;
 use crate::creature::buffs::{BuffStorage};
-use crate::player::PlayerMarker;
 
 pub fn apply_buffs<Target: Component>(mut query: Query<(&mut BuffStorage<Target>, &mut Target)>)  {
     for (mut buffs_component, mut target) in query.iter_mut() {
@@ -8,8 +7,9 @@ pub fn apply_buffs<Target: Component>(mut query: Query<(&mut BuffStorage<Target>
     }
 }
 
-pub fn clear_buffs<Target: Component>(mut query: Query<(&mut BuffStorage<Target>, &mut Target), With<PlayerMarker>>) {
+/// Bosses get their phase buffs every frame too, uncleared they would stack up.
+pub fn clear_buffs<Target: Component>(mut query: Query<(&mut BuffStorage<Target>, &mut Target)>) {
     for (mut buffs_component, mut target) in query.iter_mut() {
         buffs_component.clean(&mut target);
     }
-}
\ No newline at end of file
+}
@@ -1,59 +1,220 @@
-use crate::creature::component::attack::shooting::bullet::Bullet;
-use crate::creature::component::physiology_description::PhysiologyDescription;
+use crate::audio::DamageSoundType;
+use crate::creature::component::attack::damage::DamageRoll;
+use crate::creature::component::attack::melee;
+use crate::creature::component::attack::shooting::projectile::Projectile;
+use crate::creature::component::faction::Faction;
+use crate::creature::component::CombatParameters;
 use crate::GamePhysicsLayer;
+use bevy::math::vec3;
 use bevy::prelude::*;
-use heron::rapier_plugin::PhysicsWorld;
 use heron::{Acceleration, CollisionLayers, CollisionShape, RigidBody, Velocity};
-use std::time::Instant;
+use lib::entity::archetype::{AttackKind, AttackParams};
+use lib::entity::damage::DamageType;
+use std::time::{Duration, Instant};
 
+/// All attacks of the creature, chooses the one which fits the target.
 #[derive(Component, Debug)]
-pub struct Attack {
-    time_of_last_attack: Instant,
+pub struct AttackSet {
+    attacks: Vec<AttackParams>,
+    ready_at: Vec<Instant>,
+    action: Option<Action>,
 }
 
-impl Attack {
-    pub fn new() -> Self {
-        Attack {
-            time_of_last_attack: Instant::now(),
+/// The attack being used right now.
+#[derive(Debug, Clone, Copy)]
+struct Action {
+    attack: usize,
+    aim: Vec3,
+    shots_left: u32,
+    next_strike_at: Instant,
+}
+
+/// What the creature knows about its target.
+#[derive(Debug, Clone, Copy)]
+pub struct AttackTarget {
+    pub position: Vec3,
+    /// From the body of the creature
+    pub distance: f32,
+    pub is_visible: bool,
+}
+
+/// A single hit, shot or throw of the attack.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Strike {
+    pub attack: usize,
+    pub aim: Vec3,
+}
+
+/// The creature which strikes.
+pub struct Attacker {
+    pub entity: Entity,
+    pub faction: Faction,
+    pub position: Vec3,
+    pub eyes: Vec3,
+    pub model_radius: f32,
+}
+
+impl AttackSet {
+    pub fn new(attacks: Vec<AttackParams>) -> Self {
+        let now = Instant::now();
+        AttackSet {
+            ready_at: vec![now; attacks.len()],
+            attacks,
+            action: None,
         }
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn exec(
-        &mut self,
-        physics_world: &PhysicsWorld,
-        phys: &PhysiologyDescription,
-        transform: &Transform,
-        player_position: Vec3,
-        player_entity: Entity,
-        commands: &mut Commands,
-        meshes: &mut ResMut<Assets<Mesh>>,
-    ) {
-        if self.time_of_last_attack.elapsed().as_secs() < 1 {
-            return;
-        }
-        let eyes_pos = phys.get_eyes_position(transform);
-        let distance = eyes_pos.distance_squared(player_position);
-        if distance > 150.0 {
-            return;
+    pub fn attack(&self, index: usize) -> &AttackParams {
+        &self.attacks[index]
+    }
+
+    /// Chooses an attack for the target or continues the current one.
+    /// A started attack ends even if the target is gone, that's how the player dodges.
+    pub fn update(&mut self, target: Option<AttackTarget>) -> Option<Strike> {
+        let now = Instant::now();
+
+        if let Some(mut action) = self.action {
+            if now < action.next_strike_at {
+                return None;
+            }
+            let params = &self.attacks[action.attack];
+            if let AttackKind::Burst { interval, .. } = params.kind {
+                // Bursts follow the target they see
+                if let Some(target) = target.filter(|target| target.is_visible) {
+                    action.aim = target.position;
+                }
+                action.next_strike_at = now + Duration::from_secs_f32(interval.max(0.));
+            }
+            action.shots_left = action.shots_left.saturating_sub(1);
+
+            if action.shots_left == 0 {
+                self.action = None;
+                self.ready_at[action.attack] =
+                    now + Duration::from_secs_f32(params.cooldown.max(0.));
+            } else {
+                self.action = Some(action);
+            }
+            return Some(Strike {
+                attack: action.attack,
+                aim: action.aim,
+            });
         }
 
-        let can_see = physics_world
-            .ray_cast(eyes_pos, player_position - eyes_pos, true)
-            .map_or(true, |cast| cast.entity == player_entity);
+        let target = target?;
+        let attack = select_attack(&self.attacks, &self.ready_at, now, &target)?;
+        let params = &self.attacks[attack];
+        let shots_left = match params.kind {
+            AttackKind::Burst { shots, .. } => shots.max(1),
+            _ => 1,
+        };
+        self.action = Some(Action {
+            attack,
+            aim: target.position,
+            shots_left,
+            next_strike_at: now + Duration::from_secs_f32(params.wind_up.max(0.)),
+        });
+        None
+    }
+}
+
+/// The first attack which is ready and reaches the target.
+pub fn select_attack(
+    attacks: &[AttackParams],
+    ready_at: &[Instant],
+    now: Instant,
+    target: &AttackTarget,
+) -> Option<usize> {
+    attacks.iter().zip(ready_at).position(|(attack, ready_at)| {
+        *ready_at <= now
+            && (target.is_visible || !attack.kind.needs_sight())
+            && attack.min_range <= target.distance
+            && target.distance <= attack.range
+    })
+}
 
-        if can_see {
-            self.time_of_last_attack = Instant::now();
-            spawn_bullet(commands, meshes, player_position, eyes_pos);
+pub fn strike(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    attacker: &Attacker,
+    params: &AttackParams,
+    aim: Vec3,
+) {
+    let to_target = aim - attacker.eyes;
+    let damage = DamageRoll {
+        base: params.damage,
+        damage_type: params.damage_type,
+        crit_chance: params.crit_chance,
+    };
+    match params.kind {
+        AttackKind::Melee => {
+            let to_target = aim - attacker.position;
+            if let Some(direction) = vec3(to_target.x, 0., to_target.z).try_normalize() {
+                let combat = CombatParameters {
+                    attack_length: params.range,
+                    base_damage: params.damage,
+                    damage_type: params.damage_type,
+                    crit_chance: params.crit_chance,
+                    cooldown: Duration::from_secs_f32(params.cooldown.max(0.)),
+                    sound_type: DamageSoundType::Punch,
+                };
+                melee::spawn_sensor(
+                    commands,
+                    attacker.position,
+                    direction,
+                    &combat,
+                    attacker.model_radius,
+                    attacker.entity,
+                    attacker.faction,
+                );
+            }
+        }
+        AttackKind::SingleShot | AttackKind::Burst { .. } => {
+            let projectile = Projectile::new(
+                to_target,
+                params.projectile_speed,
+                damage,
+                attacker.entity,
+                params.projectile,
+            );
+            spawn_projectile(commands, meshes, attacker, projectile);
+        }
+        AttackKind::Spread { bullets, angle } => {
+            let angle = angle.to_radians();
+            for i in 0..bullets {
+                let turn = if bullets > 1 {
+                    -angle / 2. + angle * i as f32 / (bullets - 1) as f32
+                } else {
+                    0.
+                };
+                let direction = Quat::from_rotation_y(turn) * to_target;
+                let projectile = Projectile::new(
+                    direction,
+                    params.projectile_speed,
+                    damage,
+                    attacker.entity,
+                    params.projectile,
+                );
+                spawn_projectile(commands, meshes, attacker, projectile);
+            }
+        }
+        AttackKind::Lobbed => {
+            let projectile = Projectile::lobbed(
+                to_target,
+                params.projectile_speed,
+                damage,
+                attacker.entity,
+                params.projectile,
+            );
+            spawn_projectile(commands, meshes, attacker, projectile);
         }
     }
 }
 
-fn spawn_bullet(
+pub fn spawn_projectile(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
-    player_position: Vec3,
-    eyes_pos: Vec3,
+    attacker: &Attacker,
+    projectile: Projectile,
 ) {
     commands
         .spawn_bundle(PbrBundle {
@@ -61,10 +222,11 @@ fn spawn_bullet(
                 radius: 0.1,
                 ..Default::default()
             })),
-            transform: Transform::from_translation(eyes_pos),
+            transform: Transform::from_translation(attacker.eyes),
             ..Default::default()
         })
-        .insert(Bullet::new(player_position - eyes_pos, 0.1, 3))
+        .insert(projectile)
+        .insert(attacker.faction)
         .insert(CollisionShape::Sphere { radius: 0.1 })
         .insert(Velocity::from_linear(Vec3::default()))
         .insert(Acceleration::from_linear(Vec3::default()))
@@ -74,3 +236,85 @@ fn spawn_bullet(
                 .with_group(GamePhysicsLayer::Projectile),
         );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib::entity::archetype::ProjectileParams;
+
+    fn attack(kind: AttackKind, min_range: f32, range: f32) -> AttackParams {
+        AttackParams {
+            kind,
+            min_range,
+            range,
+            cooldown: 1.0,
+            damage: 3,
+            damage_type: DamageType::Physical,
+            crit_chance: 0.0,
+            wind_up: 0.0,
+            projectile_speed: 6.0,
+            projectile: ProjectileParams::default(),
+        }
+    }
+
+    fn target(distance: f32, is_visible: bool) -> AttackTarget {
+        AttackTarget {
+            position: Vec3::ZERO,
+            distance,
+            is_visible,
+        }
+    }
+
+    #[test]
+    fn selects_by_distance_and_sight() {
+        let attacks = [
+            attack(AttackKind::Melee, 0., 1.5),
+            attack(AttackKind::SingleShot, 3., 12.),
+            attack(AttackKind::Lobbed, 3., 12.),
+        ];
+        let now = Instant::now();
+        let ready = [now; 3];
+
+        assert_eq!(
+            select_attack(&attacks, &ready, now, &target(1., true)),
+            Some(0)
+        );
+        assert_eq!(
+            select_attack(&attacks, &ready, now, &target(2., true)),
+            None
+        );
+        assert_eq!(
+            select_attack(&attacks, &ready, now, &target(8., true)),
+            Some(1)
+        );
+        assert_eq!(
+            select_attack(&attacks, &ready, now, &target(8., false)),
+            Some(2)
+        );
+
+        let shot_cooling = [now, now + Duration::from_secs(1), now];
+        assert_eq!(
+            select_attack(&attacks, &shot_cooling, now, &target(8., true)),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn burst_strikes_every_shot() {
+        let mut set = AttackSet::new(vec![attack(
+            AttackKind::Burst {
+                shots: 2,
+                interval: 0.,
+            },
+            0.,
+            12.,
+        )]);
+        let seen = Some(target(5., true));
+
+        assert_eq!(set.update(seen), None);
+        assert!(set.update(seen).is_some());
+        // The target has left but the burst goes on
+        assert!(set.update(None).is_some());
+        assert_eq!(set.update(seen), None);
+    }
+}
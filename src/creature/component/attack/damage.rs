@@ -0,0 +1,67 @@
+use crate::creature::component::attack::event::DamageEvent;
+use bevy::prelude::*;
+use lib::entity::damage::{DamageType, ResistanceParams};
+use rand::Rng;
+
+const CRITICAL_MULTIPLIER: u16 = 2;
+
+/// Armor and resistances of the target, without it every hit is taken in full.
+#[derive(Component, Debug, Default)]
+pub struct Resistance(pub ResistanceParams);
+
+/// Damage of an attack before it hits, every hit rolls its own amount.
+#[derive(Debug, Clone, Copy)]
+pub struct DamageRoll {
+    pub base: u16,
+    pub damage_type: DamageType,
+    /// From 0 to 1
+    pub crit_chance: f32,
+}
+
+impl DamageRoll {
+    /// Up to a tenth around the base, doubled by a critical hit.
+    pub fn roll(&self) -> (u16, bool) {
+        let mut rng = rand::thread_rng();
+        let spread = self.base / 10;
+        let amount = rng.gen_range(self.base - spread..=self.base.saturating_add(spread));
+        let critical = rng.gen::<f32>() < self.crit_chance;
+
+        if critical {
+            (amount.saturating_mul(CRITICAL_MULTIPLIER), true)
+        } else {
+            (amount, false)
+        }
+    }
+}
+
+/// Every damage goes through here before it's taken from HP.
+pub fn final_amount(event: &DamageEvent, resistance: Option<&Resistance>) -> u16 {
+    resistance.map_or(event.amount, |resistance| {
+        resistance.0.reduce(event.amount, event.damage_type)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_hits_double_damage() {
+        let damage = DamageRoll {
+            base: 20,
+            damage_type: DamageType::Physical,
+            crit_chance: 1.0,
+        };
+        let (amount, critical) = damage.roll();
+        assert!(critical);
+        assert!((36..=44).contains(&amount));
+
+        let (amount, critical) = DamageRoll {
+            crit_chance: 0.0,
+            ..damage
+        }
+        .roll();
+        assert!(!critical);
+        assert!((18..=22).contains(&amount));
+    }
+}
@@ -1,9 +1,37 @@
 use crate::audio::DamageSoundType;
-use bevy::prelude::Entity;
+use bevy::prelude::{Entity, Vec3};
+use lib::entity::damage::DamageType;
 
 pub struct DamageEvent {
     pub target: Entity,
+    /// Who dealt the damage, `None` for the level itself
+    pub source: Option<Entity>,
+    /// Before the resistances of the target
     pub amount: u16,
+    pub damage_type: DamageType,
+    pub critical: bool,
     // for sound event to be produced if the damage hits the entity
     pub sound_type: DamageSoundType,
 }
+
+pub struct HealEvent {
+    pub target: Entity,
+    pub amount: u16,
+}
+
+/// Sent once when the HP of the entity drops to zero.
+pub struct Died {
+    pub entity: Entity,
+    pub killer: Option<Entity>,
+    /// The finishing blow
+    pub cause: DamageSoundType,
+}
+
+/// Sent when a projectile hits the level.
+#[derive(Clone, Copy)]
+pub struct WorldHit {
+    /// Last point in the air before the wall
+    pub position: Vec3,
+    /// Of the hit face
+    pub normal: Vec3,
+}
@@ -0,0 +1,90 @@
+use crate::audio::DamageSoundType;
+use crate::creature::component::attack::damage::DamageRoll;
+use crate::creature::component::attack::event::DamageEvent;
+use crate::creature::component::faction::Faction;
+use crate::creature::component::hp::HP;
+use crate::creature::component::CombatParameters;
+use crate::util::component::ShortLife;
+use crate::GamePhysicsLayer;
+use bevy::prelude::*;
+use heron::{CollisionEvent, CollisionLayers, CollisionShape, RigidBody};
+use std::time::Duration;
+
+// The sensor lives long enough for the physics to notice the overlap
+const STRIKE_DURATION: Duration = Duration::from_millis(150);
+
+/// Hits everything hostile in front of the attacker for a moment.
+#[derive(Component, Debug)]
+pub struct MeleeSensor {
+    attacker: Entity,
+    damage: DamageRoll,
+    sound_type: DamageSoundType,
+}
+
+pub fn spawn_sensor(
+    commands: &mut Commands,
+    position: Vec3,
+    direction: Vec3,
+    combat: &CombatParameters,
+    model_radius: f32,
+    attacker: Entity,
+    faction: Faction,
+) {
+    let center = position + direction * (combat.attack_length / 2. + model_radius);
+    commands
+        .spawn_bundle(TransformBundle::from_transform(
+            Transform::from_translation(center).looking_at(center + direction, Vec3::Y),
+        ))
+        .insert(MeleeSensor {
+            attacker,
+            damage: combat.damage(),
+            sound_type: combat.sound_type,
+        })
+        .insert(faction)
+        .insert(ShortLife::new(STRIKE_DURATION))
+        .insert(RigidBody::Sensor)
+        .insert(CollisionShape::Cuboid {
+            half_extends: Vec3::new(0.25, 0.5, combat.attack_length / 2.),
+            border_radius: None,
+        })
+        .insert(
+            CollisionLayers::all_masks::<GamePhysicsLayer>().with_group(GamePhysicsLayer::Sensor),
+        );
+}
+
+pub fn attack_check_melee_collisions(
+    mut events: EventReader<CollisionEvent>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    sensors: Query<(&MeleeSensor, &Faction)>,
+    targets: Query<&Faction, (With<HP>, Without<MeleeSensor>)>,
+) {
+    events
+        .iter()
+        .filter(|e| e.is_started())
+        .filter_map(|event| {
+            let (entity_1, entity_2) = event.rigid_body_entities();
+            let (sensor, target) = if sensors.get(entity_1).is_ok() {
+                (entity_1, entity_2)
+            } else if sensors.get(entity_2).is_ok() {
+                (entity_2, entity_1)
+            } else {
+                return None;
+            };
+
+            let (melee, faction) = sensors.get(sensor).ok()?;
+            let target_faction = targets.get(target).ok()?;
+            (target != melee.attacker && faction.is_hostile_to(*target_faction))
+                .then_some((target, melee))
+        })
+        .for_each(|(target, melee)| {
+            let (amount, critical) = melee.damage.roll();
+            ev_damage.send(DamageEvent {
+                target,
+                source: Some(melee.attacker),
+                amount,
+                damage_type: melee.damage.damage_type,
+                critical,
+                sound_type: melee.sound_type,
+            });
+        });
+}
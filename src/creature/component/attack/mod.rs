@@ -1,33 +1,43 @@
-use crate::creature::component::attack::event::DamageEvent;
+use crate::creature::component::attack::event::{DamageEvent, Died, HealEvent, WorldHit};
+use crate::creature::component::attack::melee::attack_check_melee_collisions;
 use crate::creature::component::attack::number::{
-    attack_animate_damage_numbers, attack_setup_damage_number_assets,
+    attack_animate_damage_numbers, attack_setup_damage_number_assets, attack_show_damage_numbers,
+    NumberEvent,
 };
 use crate::creature::component::attack::system::{
-    attack_apply_damage, attack_check_bullet_collisions, attack_despawn_killed_entities,
-    attack_launch_bullets,
+    attack_apply_damage, attack_apply_heals, attack_check_projectile_collisions,
+    attack_move_projectiles,
 };
 use crate::GameState;
 use bevy::prelude::*;
 
 pub mod component;
+pub mod damage;
 pub mod event;
-mod number;
+pub mod melee;
+pub mod number;
 pub mod shooting;
-mod system;
+pub mod system;
 
 pub struct AttackPlugin;
 
 impl Plugin for AttackPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<DamageEvent>()
+            .add_event::<Died>()
+            .add_event::<HealEvent>()
+            .add_event::<NumberEvent>()
+            .add_event::<WorldHit>()
             .add_startup_system(attack_setup_damage_number_assets)
             .add_system_set(
                 SystemSet::on_update(GameState::Playing)
-                    .with_system(attack_despawn_killed_entities)
-                    .with_system(attack_launch_bullets)
+                    .with_system(attack_move_projectiles)
                     .with_system(attack_apply_damage)
+                    .with_system(attack_apply_heals)
+                    .with_system(attack_show_damage_numbers)
                     .with_system(attack_animate_damage_numbers)
-                    .with_system(attack_check_bullet_collisions),
+                    .with_system(attack_check_projectile_collisions)
+                    .with_system(attack_check_melee_collisions),
             );
     }
 }
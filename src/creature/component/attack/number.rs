@@ -1,15 +1,64 @@
 use crate::util::component::ShortLife;
-use bevy::math::vec3;
 use bevy::prelude::*;
+use lib::util::game_settings::GameSettings;
 use rand::Rng;
 use std::f32::consts::{FRAC_PI_6, PI};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-#[derive(Component)]
-pub struct DamageNumberMarker;
+const LIFETIME: Duration = Duration::from_millis(500);
+// Hits on one target closer in time than this add up in one number, if enabled
+const MERGE_WINDOW: Duration = Duration::from_millis(300);
+// Distance between the centers of the digits at scale 1
+const DIGIT_SPACING: f32 = 0.4;
+const CRITICAL_SCALE: f32 = 1.5;
+const HEAL_SCALE: f32 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberKind {
+    Damage,
+    Critical,
+    Heal,
+}
+
+impl NumberKind {
+    /// Damage merges with damage, heals with heals.
+    fn merges_with(self, other: NumberKind) -> bool {
+        (self == NumberKind::Heal) == (other == NumberKind::Heal)
+    }
+
+    /// One critical hit makes the whole merged number critical.
+    fn combine(self, other: NumberKind) -> NumberKind {
+        if other == NumberKind::Critical {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Shows a rising number above the target.
+#[derive(Clone, Copy)]
+pub struct NumberEvent {
+    pub target: Entity,
+    pub position: Vec3,
+    pub amount: u16,
+    pub kind: NumberKind,
+}
+
+#[derive(Component, Debug)]
+pub struct DamageNumber {
+    target: Entity,
+    total: u32,
+    kind: NumberKind,
+    scale: f32,
+    material: Handle<StandardMaterial>,
+    last_hit: Instant,
+}
 
 pub struct DamageNumberAssets {
     materials: Vec<Handle<StandardMaterial>>,
+    critical: Handle<StandardMaterial>,
+    heal: Handle<StandardMaterial>,
     meshes: Vec<Handle<Mesh>>,
 }
 
@@ -28,18 +77,20 @@ pub fn attack_setup_damage_number_assets(
         Color::PURPLE,
         Color::TOMATO,
     ];
-
-    let mut mat = vec![];
-    for color in colors {
-        let material = StandardMaterial {
+    let mut material = |color: Color| {
+        materials.add(StandardMaterial {
             base_color: color,
             unlit: true,
             ..Default::default()
-        };
+        })
+    };
 
-        let id = materials.add(material);
-        mat.push(id);
+    let mut mat = vec![];
+    for color in colors {
+        mat.push(material(color));
     }
+    let critical = material(Color::GOLD);
+    let heal = material(Color::LIME_GREEN);
 
     let mut meshes = vec![];
     for number in 0..=9 {
@@ -50,29 +101,77 @@ pub fn attack_setup_damage_number_assets(
 
     commands.insert_resource(DamageNumberAssets {
         materials: mat,
+        critical,
+        heal,
         meshes,
     });
 }
 
-pub fn attack_animate_damage_numbers(mut numbers: Query<&mut Transform, With<DamageNumberMarker>>) {
+pub fn attack_animate_damage_numbers(mut numbers: Query<&mut Transform, With<DamageNumber>>) {
     for mut t in numbers.iter_mut() {
         t.translation.y += 0.05;
     }
 }
 
-pub fn spawn(
-    commands: &mut Commands,
-    numbers: &DamageNumberAssets,
-    position: &Transform,
-    number: u16,
+pub fn attack_show_damage_numbers(
+    mut commands: Commands,
+    mut number_events: EventReader<NumberEvent>,
+    settings: Res<GameSettings>,
+    assets: Res<DamageNumberAssets>,
+    mut numbers: Query<(Entity, &mut DamageNumber, &mut ShortLife)>,
 ) {
+    let merge = settings.get_merge_damage_numbers();
+    // Numbers spawned in this frame can't be queried yet, so hits of the frame merge first
+    let mut hits: Vec<NumberEvent> = vec![];
+    for event in number_events.iter() {
+        let same_target = hits
+            .iter_mut()
+            .find(|hit| merge && hit.target == event.target && hit.kind.merges_with(event.kind));
+        if let Some(hit) = same_target {
+            hit.amount = hit.amount.saturating_add(event.amount);
+            hit.kind = hit.kind.combine(event.kind);
+        } else {
+            hits.push(*event);
+        }
+    }
+
+    for hit in hits {
+        let recent = numbers.iter_mut().find(|(_, number, _)| {
+            merge
+                && number.target == hit.target
+                && number.kind.merges_with(hit.kind)
+                && number.last_hit.elapsed() < MERGE_WINDOW
+        });
+
+        if let Some((entity, mut number, mut life)) = recent {
+            let kind = number.kind.combine(hit.kind);
+            if kind != number.kind {
+                let (scale, material) = style(&assets, kind);
+                number.scale = scale;
+                number.material = material;
+            }
+            number.kind = kind;
+            number.total += u32::from(hit.amount);
+            number.last_hit = Instant::now();
+            *life = ShortLife::new(LIFETIME);
+
+            let mut entity_commands = commands.entity(entity);
+            entity_commands.despawn_descendants();
+            entity_commands.with_children(|builder| spawn_digits(builder, &assets, &number));
+        } else {
+            spawn(&mut commands, &assets, &hit);
+        }
+    }
+}
+
+fn spawn(commands: &mut Commands, assets: &DamageNumberAssets, hit: &NumberEvent) {
     let mut rng = rand::thread_rng();
     let x_bonus = rng.gen_range(-5..5);
     let z_bonus = rng.gen_range(-5..5);
     let transform = Transform::from_xyz(
-        position.translation.x + x_bonus as f32 / 10.,
-        position.translation.y + 2.5,
-        position.translation.z + z_bonus as f32 / 10.,
+        hit.position.x + x_bonus as f32 / 10.,
+        hit.position.y + 2.5,
+        hit.position.z + z_bonus as f32 / 10.,
     )
     // TODO more accurate rotation to the camera
     .with_rotation(Quat::from_euler(
@@ -82,52 +181,78 @@ pub fn spawn(
         0.,
     ));
 
-    let scale = rng.gen_range(45..100) as f32 / 100.;
+    let (scale, material) = style(assets, hit.kind);
+    let number = DamageNumber {
+        target: hit.target,
+        total: u32::from(hit.amount),
+        kind: hit.kind,
+        scale,
+        material,
+        last_hit: Instant::now(),
+    };
     commands
         .spawn_bundle(TransformBundle::from_transform(transform))
         .insert_bundle(VisibilityBundle::default())
-        .insert(DamageNumberMarker)
-        .insert(ShortLife::new(Duration::from_millis(500)))
-        .with_children(|builder| {
-            spawn_number(builder, numbers, number, scale);
+        .insert(ShortLife::new(LIFETIME))
+        .with_children(|builder| spawn_digits(builder, assets, &number))
+        .insert(number);
+}
+
+fn style(assets: &DamageNumberAssets, kind: NumberKind) -> (f32, Handle<StandardMaterial>) {
+    match kind {
+        NumberKind::Damage => {
+            let mut rng = rand::thread_rng();
+            let index = rng.gen_range(0..assets.materials.len());
+            let scale = rng.gen_range(45..100) as f32 / 100.;
+            (scale, assets.materials[index].clone())
+        }
+        NumberKind::Critical => (CRITICAL_SCALE, assets.critical.clone()),
+        NumberKind::Heal => (HEAL_SCALE, assets.heal.clone()),
+    }
+}
+
+fn spawn_digits(builder: &mut ChildBuilder, assets: &DamageNumberAssets, number: &DamageNumber) {
+    // Small numbers keep the spacing so their digits don't stick together
+    let spacing = DIGIT_SPACING * number.scale.max(1.);
+    for (digit, x) in digit_offsets(number.total, spacing) {
+        let transform = Transform::from_xyz(x, 0., 0.).with_scale(Vec3::splat(number.scale));
+
+        builder.spawn_bundle(PbrBundle {
+            mesh: assets.meshes[digit].clone(),
+            material: number.material.clone(),
+            transform,
+            ..Default::default()
         });
+    }
 }
 
-fn spawn_number(builder: &mut ChildBuilder, numbers: &DamageNumberAssets, number: u16, scale: f32) {
-    let mut rng = rand::thread_rng();
-    let index = rng.gen_range(0..numbers.materials.len());
-    let material = numbers.materials[index].clone();
-
-    if number <= 9 {
-        spawn_single_number(builder, number, 0.0, material, scale, numbers);
-    } else {
-        let n = number
-            .to_string()
-            .chars()
-            .flat_map(|v| v.to_string().parse::<u16>())
-            .collect::<Vec<u16>>();
-
-        // TODO bigger numbers? like 123
-        spawn_single_number(builder, n[0], -0.2, material.clone(), scale, numbers);
-        spawn_single_number(builder, n[1], 0.2, material, scale, numbers);
-    };
+/// Every digit with its shift from the center of the number.
+fn digit_offsets(number: u32, spacing: f32) -> Vec<(usize, f32)> {
+    let digits: Vec<usize> = number
+        .to_string()
+        .bytes()
+        .map(|digit| usize::from(digit - b'0'))
+        .collect();
+    let center = (digits.len() - 1) as f32 / 2.;
+
+    digits
+        .into_iter()
+        .enumerate()
+        .map(|(i, digit)| (digit, (i as f32 - center) * spacing))
+        .collect()
 }
 
-fn spawn_single_number(
-    builder: &mut ChildBuilder,
-    number: u16,
-    x_bonus: f32,
-    material: Handle<StandardMaterial>,
-    scale: f32,
-    numbers: &DamageNumberAssets,
-) {
-    let mesh = numbers.meshes[number as usize].clone();
-    let transform = Transform::from_xyz(0. + x_bonus, 0., 0.).with_scale(vec3(scale, scale, scale));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    builder.spawn_bundle(PbrBundle {
-        mesh,
-        material,
-        transform,
-        ..Default::default()
-    });
+    #[test]
+    fn numbers_have_all_digits() {
+        assert_eq!(digit_offsets(7, 0.4), vec![(7, 0.)]);
+        assert_eq!(digit_offsets(42, 0.4), vec![(4, -0.2), (2, 0.2)]);
+        assert_eq!(
+            digit_offsets(1203, 1.),
+            vec![(1, -1.5), (2, -0.5), (0, 0.5), (3, 1.5)]
+        );
+    }
 }
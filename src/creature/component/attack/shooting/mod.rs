@@ -1 +1 @@
-pub mod bullet;
+pub mod projectile;
@@ -0,0 +1,227 @@
+use crate::audio::DamageSoundType;
+use crate::creature::component::attack::damage::DamageRoll;
+use crate::creature::component::attack::event::WorldHit;
+use bevy::math::vec3;
+use bevy::prelude::*;
+use lib::entity::archetype::ProjectileParams;
+use lib::entity::level::Level;
+use lib::entity::voxel::Material;
+
+// Fall of lobbed projectiles if the attack doesn't set its own gravity
+const LOB_GRAVITY: f32 = 18.0;
+// Throws at the feet still fly for a moment
+const MIN_LOB_TIME: f32 = 0.1;
+// Fast projectiles are checked against the level by such steps, so they don't fly through walls
+const WORLD_CHECK_STEP: f32 = 0.5;
+
+/// Bullet, throw or anything else flying until it hits or runs out of range.
+#[derive(Component, Debug)]
+pub struct Projectile {
+    velocity: Vec3,
+    gravity: f32,
+    pub damage: DamageRoll,
+    pub shooter: Entity,
+    pub sound_type: DamageSoundType,
+    params: ProjectileParams,
+    travelled: f32,
+    age: f32,
+    /// Pierced targets aren't hit twice
+    hit: Vec<Entity>,
+    bounces: u8,
+}
+
+impl Projectile {
+    /// Flies straight with `speed` per second, unless the params give it gravity.
+    pub fn new(
+        direction: Vec3,
+        speed: f32,
+        damage: DamageRoll,
+        shooter: Entity,
+        params: ProjectileParams,
+    ) -> Projectile {
+        let velocity = direction.normalize_or_zero() * speed;
+        let gravity = params.gravity.unwrap_or(0.);
+        Projectile::flying(velocity, gravity, damage, shooter, params)
+    }
+
+    /// Flies by an arc and comes down at the end of the `path`.
+    pub fn lobbed(
+        path: Vec3,
+        speed: f32,
+        damage: DamageRoll,
+        shooter: Entity,
+        params: ProjectileParams,
+    ) -> Projectile {
+        let gravity = params.gravity.unwrap_or(LOB_GRAVITY);
+        let horizontal = vec3(path.x, 0., path.z);
+        let time = (horizontal.length() / speed).max(MIN_LOB_TIME);
+        // The height after t seconds is vy * t - g * t^2 / 2
+        let vertical = (path.y + gravity * time * time / 2.) / time;
+        let velocity = horizontal / time + vec3(0., vertical, 0.);
+
+        Projectile::flying(velocity, gravity, damage, shooter, params)
+    }
+
+    fn flying(
+        velocity: Vec3,
+        gravity: f32,
+        damage: DamageRoll,
+        shooter: Entity,
+        params: ProjectileParams,
+    ) -> Projectile {
+        Projectile {
+            velocity,
+            gravity,
+            damage,
+            shooter,
+            sound_type: DamageSoundType::Bullet,
+            params,
+            travelled: 0.,
+            age: 0.,
+            hit: vec![],
+            bounces: 0,
+        }
+    }
+
+    /// Hits sound like the weapon, not like a bullet.
+    pub fn with_sound(mut self, sound_type: DamageSoundType) -> Projectile {
+        self.sound_type = sound_type;
+        self
+    }
+
+    /// Moves the projectile by `delta` seconds, returns its shift.
+    pub fn fly(&mut self, delta: f32) -> Vec3 {
+        let fall = vec3(0., self.gravity * delta * delta / 2., 0.);
+        let shift = self.velocity * delta - fall;
+        self.velocity.y -= self.gravity * delta;
+        self.travelled += shift.length();
+        self.age += delta;
+        shift
+    }
+
+    /// Flew its range, lived its time or broke on a target or a wall.
+    pub fn is_spent(&self) -> bool {
+        self.travelled >= self.params.max_range
+            || self.age >= self.params.lifetime
+            || self.hit.len() > usize::from(self.params.pierce)
+            || self.bounces > self.params.ricochet
+    }
+
+    pub fn has_hit(&self, target: Entity) -> bool {
+        self.hit.contains(&target)
+    }
+
+    /// Remembers the target, returns whether the projectile flies on through it.
+    pub fn pierce(&mut self, target: Entity) -> bool {
+        self.hit.push(target);
+        !self.is_spent()
+    }
+
+    /// Turns away from the wall with the `normal`, returns whether it bounced.
+    pub fn ricochet(&mut self, normal: Vec3) -> bool {
+        self.bounces = self.bounces.saturating_add(1);
+        self.velocity -= 2. * self.velocity.dot(normal) * normal;
+        !self.is_spent()
+    }
+}
+
+/// The first wall on the way from `from` to `to`, water doesn't stop projectiles.
+pub fn world_hit(lvl: &Level, from: Vec3, to: Vec3) -> Option<WorldHit> {
+    let steps = (from.distance(to) / WORLD_CHECK_STEP).ceil().max(1.) as usize;
+    let mut previous = from;
+
+    for step in 1..=steps {
+        let point = from.lerp(to, step as f32 / steps as f32);
+        let voxel = point.floor();
+        let is_solid = voxel.min_element() >= 0.
+            && lvl
+                .get_voxel_by_point(&voxel)
+                .map_or(false, |voxel| voxel.material != Material::Water);
+
+        if is_solid {
+            // The faces crossed to get into the voxel, the way back if it started inside
+            let crossed = (previous.floor() - voxel).clamp(Vec3::NEG_ONE, Vec3::ONE);
+            let normal = crossed
+                .try_normalize()
+                .unwrap_or_else(|| (from - to).normalize_or_zero());
+            return Some(WorldHit {
+                position: previous,
+                normal,
+            });
+        }
+        previous = point;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib::entity::damage::DamageType;
+    use lib::entity::level::DayPart;
+    use lib::entity::voxel::{Shape, Voxel};
+
+    fn damage() -> DamageRoll {
+        DamageRoll {
+            base: 1,
+            damage_type: DamageType::Physical,
+            crit_chance: 0.,
+        }
+    }
+
+    fn params(pierce: u8, ricochet: u8) -> ProjectileParams {
+        ProjectileParams {
+            pierce,
+            ricochet,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn projectiles_keep_speed_and_range() {
+        let shooter = Entity::from_raw(0);
+        let straight = Projectile::new(Vec3::X, 10., damage(), shooter, params(0, 0));
+        let diagonal = Projectile::new(vec3(1., 0., 1.), 10., damage(), shooter, params(0, 0));
+        assert!((straight.velocity.length() - diagonal.velocity.length()).abs() < 1e-4);
+
+        let mut bullet = Projectile::new(Vec3::X, 10., damage(), shooter, params(0, 0));
+        let frames = (0..1000).take_while(|_| {
+            bullet.fly(0.1);
+            !bullet.is_spent()
+        });
+        assert_eq!(frames.count(), 49);
+
+        let path = vec3(8., 1., 6.);
+        let mut lobbed = Projectile::lobbed(path, 5., damage(), shooter, params(0, 0));
+        let landing = (0..20).fold(Vec3::ZERO, |position, _| position + lobbed.fly(0.1));
+        assert!(landing.distance(path) < 1e-3);
+    }
+
+    #[test]
+    fn projectiles_pierce_and_ricochet() {
+        let mut projectile =
+            Projectile::new(Vec3::X, 10., damage(), Entity::from_raw(0), params(1, 1));
+        assert!(projectile.pierce(Entity::from_raw(1)));
+        assert!(projectile.has_hit(Entity::from_raw(1)));
+        assert!(!projectile.pierce(Entity::from_raw(2)));
+
+        let mut projectile =
+            Projectile::new(Vec3::X, 10., damage(), Entity::from_raw(0), params(0, 1));
+        assert!(projectile.ricochet(Vec3::NEG_X));
+        assert_eq!(projectile.velocity, vec3(-10., 0., 0.));
+        assert!(!projectile.ricochet(Vec3::X));
+    }
+
+    #[test]
+    fn walls_stop_projectiles() {
+        let voxels = (0..4)
+            .map(|y| Voxel::new(vec3(5., y as f32, 2.), Material::Solid, Shape::Cube))
+            .collect();
+        let lvl = Level::new("test".to_string(), voxels, DayPart::Day, vec![]);
+
+        let hit = world_hit(&lvl, vec3(3.5, 1.5, 2.5), vec3(6.5, 1.5, 2.5)).unwrap();
+        assert_eq!(hit.normal, Vec3::NEG_X);
+        assert!(hit.position.x < 5. && hit.position.x > 4.);
+        assert!(world_hit(&lvl, vec3(3.5, 1.5, 3.5), vec3(6.5, 1.5, 3.5)).is_none());
+    }
+}
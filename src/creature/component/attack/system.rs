@@ -1,117 +1,181 @@
 use crate::audio::{DamageSoundType, SoundEvent, SoundLayer, SoundType};
-use crate::creature::component::attack::event::DamageEvent;
-use crate::creature::component::attack::number;
-use crate::creature::component::attack::number::DamageNumberAssets;
-use crate::creature::component::attack::shooting::bullet::Bullet;
+use crate::creature::component::attack::damage::{self, Resistance};
+use crate::creature::component::attack::event::{DamageEvent, Died, HealEvent, WorldHit};
+use crate::creature::component::attack::number::{NumberEvent, NumberKind};
+use crate::creature::component::attack::shooting::projectile::{self, Projectile};
+use crate::creature::component::faction::Faction;
 use crate::creature::component::hp::HP;
-use crate::particle::PunchEffect;
-use crate::GamePhysicsLayer;
+use crate::particle::{ImpactEffect, PunchEffect};
 use bevy::prelude::*;
 use bevy_hanabi::ParticleEffect;
-use heron::{CollisionEvent, CollisionLayers};
-use rand::Rng;
-use std::cmp;
+use heron::CollisionEvent;
+use lib::entity::level::Level;
+use lib::entity::weapon::{HitEffect, Weapons};
 use std::f32::consts::{FRAC_PI_6, PI};
 
-pub fn attack_despawn_killed_entities(mut commands: Commands, entities: Query<(Entity, &HP)>) {
-    for (entity, hp) in entities.iter() {
-        if hp.is_empty() {
-            commands.entity(entity).despawn_recursive();
-        }
-    }
-}
+pub fn attack_move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    lvl: Res<Level>,
+    mut sound_events: EventWriter<SoundEvent>,
+    mut hit_events: EventWriter<WorldHit>,
+    mut projectiles: Query<(Entity, &mut Transform, &mut Projectile)>,
+) {
+    for (entity, mut transform, mut projectile) in projectiles.iter_mut() {
+        let from = transform.translation;
+        let to = from + projectile.fly(time.delta_seconds());
 
-pub fn attack_launch_bullets(mut bullets: Query<(&mut Transform, &Bullet)>) {
-    for (mut transform, bullet) in bullets.iter_mut() {
-        transform.translation += bullet.shift;
+        if let Some(hit) = projectile::world_hit(&lvl, from, to) {
+            transform.translation = hit.position;
+            if !projectile.ricochet(hit.normal) {
+                commands.entity(entity).despawn();
+            }
+            sound_events.send(SoundEvent {
+                sound_layer: SoundLayer::ForeGround,
+                sound_type: SoundType::Damage(DamageSoundType::Wall),
+                position: Some(hit.position),
+                source: Some(projectile.shooter),
+            });
+            hit_events.send(hit);
+        } else if projectile.is_spent() {
+            commands.entity(entity).despawn();
+        } else {
+            transform.translation = to;
+        }
     }
 }
 
 pub fn attack_apply_damage(
-    mut commands: Commands,
+    weapons: Res<Weapons>,
     mut damage_events: EventReader<DamageEvent>,
     mut sound_events: EventWriter<SoundEvent>,
-    mut targets: Query<(&mut HP, &Transform)>,
-    numbers: Res<DamageNumberAssets>,
-    mut hit_effects: Query<(&mut ParticleEffect, &mut Transform), (With<PunchEffect>, Without<HP>)>,
+    mut died_events: EventWriter<Died>,
+    mut number_events: EventWriter<NumberEvent>,
+    mut targets: Query<(&mut HP, &Transform, Option<&Resistance>)>,
+    mut hit_effects: ParamSet<(
+        Query<(&mut ParticleEffect, &mut Transform), (With<PunchEffect>, Without<HP>)>,
+        Query<(&mut ParticleEffect, &mut Transform), (With<ImpactEffect>, Without<HP>)>,
+    )>,
 ) {
-    let (mut effect, mut effect_transform) = hit_effects.single_mut();
-
     for ev in damage_events.iter() {
-        if let Ok((mut hp, transform)) = targets.get_mut(ev.target) {
-            hp.sub(ev.amount);
+        // The fight is heard where the attacker is, the victim would only find itself there
+        let attacker = ev
+            .source
+            .and_then(|source| targets.get(source).ok())
+            .map(|(_, transform, _)| transform.translation);
+        if let Ok((mut hp, transform, resistance)) = targets.get_mut(ev.target) {
+            let amount = damage::final_amount(ev, resistance);
+            let was_alive = !hp.is_empty();
+            hp.sub(amount);
+            if was_alive && hp.is_empty() {
+                died_events.send(Died {
+                    entity: ev.target,
+                    killer: ev.source,
+                    cause: ev.sound_type,
+                });
+            }
             sound_events.send(SoundEvent {
                 sound_layer: SoundLayer::ForeGround,
                 sound_type: SoundType::Damage(ev.sound_type),
+                position: attacker,
+                source: ev.source,
             });
 
-            number::spawn(&mut commands, &numbers, transform, ev.amount);
+            number_events.send(NumberEvent {
+                target: ev.target,
+                position: transform.translation,
+                amount,
+                kind: if ev.critical {
+                    NumberKind::Critical
+                } else {
+                    NumberKind::Damage
+                },
+            });
 
-            effect_transform.translation = transform.translation;
-            effect_transform.rotation =
-                Quat::from_euler(EulerRot::XYZ, -FRAC_PI_6, -(PI - FRAC_PI_6), 0.);
-            effect.maybe_spawner().unwrap().reset();
+            let hit_effect = match ev.sound_type {
+                DamageSoundType::Weapon(weapon) => weapons.get(weapon).hit_effect,
+                _ => HitEffect::Punch,
+            };
+            match hit_effect {
+                HitEffect::Punch => show_hit(hit_effects.p0().single_mut(), transform.translation),
+                HitEffect::Sparks => show_hit(hit_effects.p1().single_mut(), transform.translation),
+            }
         }
     }
 }
 
-pub fn attack_check_bullet_collisions(
-    mut commands: Commands,
-    mut events: EventReader<CollisionEvent>,
-    mut ev_damage: EventWriter<DamageEvent>,
-    bullets: Query<&Bullet>,
+fn show_hit(
+    (mut effect, mut effect_transform): (Mut<ParticleEffect>, Mut<Transform>),
+    position: Vec3,
 ) {
-    let mut rng = rand::thread_rng();
-
-    events
-        .iter()
-        .filter(|e| e.is_started())
-        .filter_map(|event| {
-            let (entity_1, entity_2) = event.rigid_body_entities();
-            let (layers_1, layers_2) = event.collision_layers();
-            let with_bullet = is_bullet(layers_1) || is_bullet(layers_2);
-            let with_player = is_player(layers_1) || is_player(layers_2);
+    effect_transform.translation = position;
+    effect_transform.rotation = Quat::from_euler(EulerRot::XYZ, -FRAC_PI_6, -(PI - FRAC_PI_6), 0.);
+    effect.maybe_spawner().unwrap().reset();
+}
 
-            if with_bullet {
-                if is_bullet(layers_1) {
-                    commands.entity(entity_1).despawn();
-                } else {
-                    commands.entity(entity_2).despawn();
-                }
+pub fn attack_apply_heals(
+    mut heal_events: EventReader<HealEvent>,
+    mut number_events: EventWriter<NumberEvent>,
+    mut targets: Query<(&mut HP, &Transform)>,
+) {
+    for event in heal_events.iter() {
+        if let Ok((mut hp, transform)) = targets.get_mut(event.target) {
+            let healed = hp.heal(event.amount);
+            if healed > 0 {
+                number_events.send(NumberEvent {
+                    target: event.target,
+                    position: transform.translation,
+                    amount: healed,
+                    kind: NumberKind::Heal,
+                });
             }
+        }
+    }
+}
 
-            if with_bullet && with_player {
-                if is_player(layers_1) {
-                    return Some((entity_1, entity_2));
-                }
-                return Some((entity_2, entity_1));
-            }
+/// The level is checked by voxels, so only creatures and the player stop projectiles.
+/// Projectiles of the player hurt the same creatures as the thrust.
+pub fn attack_check_projectile_collisions(
+    mut commands: Commands,
+    mut events: EventReader<CollisionEvent>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    mut projectiles: Query<(&mut Projectile, &Faction)>,
+    targets: Query<&Faction, Without<Projectile>>,
+) {
+    for event in events.iter().filter(|e| e.is_started()) {
+        let (entity_1, entity_2) = event.rigid_body_entities();
+        let (entity, target) = if projectiles.get(entity_1).is_ok() {
+            (entity_1, entity_2)
+        } else if projectiles.get(entity_2).is_ok() {
+            (entity_2, entity_1)
+        } else {
+            continue;
+        };
+        let target_faction = if let Ok(faction) = targets.get(target) {
+            faction
+        } else {
+            continue;
+        };
+        let (mut projectile, faction) = projectiles
+            .get_mut(entity)
+            .expect("Projectile should exist");
+        if target == projectile.shooter || projectile.has_hit(target) || projectile.is_spent() {
+            continue;
+        }
 
-            None
-        })
-        .for_each(|(target, bullet)| {
-            let base_damage = bullets.get(bullet).expect("Bullet should exist").damage;
-            let damage = rng.gen_range(
-                cmp::min(base_damage - (base_damage / 10), base_damage - 1)
-                    ..cmp::max(base_damage + (base_damage / 10), base_damage + 1),
-            );
+        if faction.can_hurt(*target_faction) {
+            let (amount, critical) = projectile.damage.roll();
             ev_damage.send(DamageEvent {
                 target,
-                amount: damage,
-                sound_type: DamageSoundType::Bullet,
+                source: Some(projectile.shooter),
+                amount,
+                damage_type: projectile.damage.damage_type,
+                critical,
+                sound_type: projectile.sound_type,
             });
-        });
-}
-
-fn is_bullet(layers: CollisionLayers) -> bool {
-    layers.contains_group(GamePhysicsLayer::Projectile)
-        && !layers.contains_group(GamePhysicsLayer::Player)
-        && !layers.contains_group(GamePhysicsLayer::World)
-        && !layers.contains_group(GamePhysicsLayer::Creature)
-}
-fn is_player(layers: CollisionLayers) -> bool {
-    layers.contains_group(GamePhysicsLayer::Player)
-        && !layers.contains_group(GamePhysicsLayer::Projectile)
-        && !layers.contains_group(GamePhysicsLayer::World)
-        && !layers.contains_group(GamePhysicsLayer::Creature)
+        }
+        if !projectile.pierce(target) {
+            commands.entity(entity).despawn();
+        }
+    }
 }
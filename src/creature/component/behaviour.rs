@@ -0,0 +1,283 @@
+use crate::creature::component::movement::MovementStrategy;
+use crate::creature::component::perception::Perception;
+use bevy::prelude::*;
+use std::mem;
+use std::time::{Duration, Instant};
+
+// Attacking creature doesn't switch back to chase on every small step of the target
+const ATTACK_RANGE_TOLERANCE: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BehaviourState {
+    Idle,
+    /// Noticed something, looks around or checks the last known position of the target
+    Alert,
+    Chase,
+    Attack,
+    Flee,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MovementSlot {
+    Idle,
+    Pursuit,
+    Flee,
+}
+
+impl BehaviourState {
+    fn movement(self) -> MovementSlot {
+        match self {
+            BehaviourState::Idle => MovementSlot::Idle,
+            BehaviourState::Alert | BehaviourState::Chase | BehaviourState::Attack => {
+                MovementSlot::Pursuit
+            }
+            BehaviourState::Flee => MovementSlot::Flee,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BehaviourConfig {
+    pub attack_range: f32,
+    /// Delay between noticing a target and reacting
+    pub reaction_time: Duration,
+    /// How long the creature stays alerted before returning to idle
+    pub alert_duration: Duration,
+    /// Part of HP below which the creature runs away, 0 for the brave ones
+    pub flee_below_hp: f32,
+}
+
+/// What the creature knows right now, the input of the transitions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Senses {
+    /// Distance to the nearest target the creature sees
+    pub target_distance: Option<f32>,
+    /// Has seen or heard a target recently
+    pub remembers_target: bool,
+    pub hp: f32,
+}
+
+/// Decides what the creature is doing and owns the movement strategies of all states.
+/// The strategy of the current state lives in the `MovementStrategy` component.
+#[derive(Component, Debug)]
+pub struct Behaviour {
+    config: BehaviourConfig,
+    state: BehaviourState,
+    entered_at: Instant,
+    idle: Option<MovementStrategy>,
+    pursuit: Option<MovementStrategy>,
+    flee: Option<MovementStrategy>,
+}
+
+impl Behaviour {
+    /// The creature starts idle with its idle strategy as `MovementStrategy`.
+    pub fn new(config: BehaviourConfig, pursuit: MovementStrategy, flee: MovementStrategy) -> Self {
+        Behaviour {
+            config,
+            state: BehaviourState::Idle,
+            entered_at: Instant::now(),
+            idle: None,
+            pursuit: Some(pursuit),
+            flee: Some(flee),
+        }
+    }
+
+    pub fn state(&self) -> BehaviourState {
+        self.state
+    }
+
+    pub fn time_in_state(&self) -> Duration {
+        self.entered_at.elapsed()
+    }
+
+    /// The attacks decide themselves whether they reach the target.
+    pub fn can_attack(&self) -> bool {
+        matches!(self.state, BehaviourState::Chase | BehaviourState::Attack)
+    }
+
+    pub fn update(
+        &mut self,
+        perception: &Perception,
+        position: Vec3,
+        hp: f32,
+        movement: &mut MovementStrategy,
+    ) {
+        let senses = Senses {
+            target_distance: perception
+                .visible_target()
+                .map(|target| target.distance(position)),
+            remembers_target: perception.memory().is_some(),
+            hp,
+        };
+
+        let next = next_state(self.state, self.time_in_state(), &senses, &self.config);
+        if next != self.state {
+            self.switch_movement(next, movement);
+            self.state = next;
+            self.entered_at = Instant::now();
+        }
+    }
+
+    /// Swaps the config and the strategies, the strategy of the current state starts at once.
+    pub fn reconfigure(
+        &mut self,
+        config: BehaviourConfig,
+        pursuit: MovementStrategy,
+        flee: MovementStrategy,
+        movement: &mut MovementStrategy,
+    ) {
+        self.config = config;
+        self.pursuit = Some(pursuit);
+        self.flee = Some(flee);
+
+        let current = self.state.movement();
+        if current != MovementSlot::Idle {
+            *movement = self
+                .slot(current)
+                .take()
+                .expect("Movement strategy of the state should be stored");
+        }
+    }
+
+    fn switch_movement(&mut self, next: BehaviourState, movement: &mut MovementStrategy) {
+        let (from, to) = (self.state.movement(), next.movement());
+        if from == to {
+            return;
+        }
+
+        let next_movement = self
+            .slot(to)
+            .take()
+            .expect("Movement strategy of the state should be stored");
+        let previous = mem::replace(movement, next_movement);
+        *self.slot(from) = Some(previous);
+    }
+
+    fn slot(&mut self, slot: MovementSlot) -> &mut Option<MovementStrategy> {
+        match slot {
+            MovementSlot::Idle => &mut self.idle,
+            MovementSlot::Pursuit => &mut self.pursuit,
+            MovementSlot::Flee => &mut self.flee,
+        }
+    }
+}
+
+pub fn next_state(
+    state: BehaviourState,
+    time_in_state: Duration,
+    senses: &Senses,
+    config: &BehaviourConfig,
+) -> BehaviourState {
+    if senses.hp <= config.flee_below_hp && senses.remembers_target {
+        return BehaviourState::Flee;
+    }
+
+    let engage = |distance: f32, tolerance: f32| {
+        if distance <= config.attack_range + tolerance {
+            BehaviourState::Attack
+        } else {
+            BehaviourState::Chase
+        }
+    };
+
+    match (state, senses.target_distance) {
+        (BehaviourState::Idle, _) if senses.remembers_target => BehaviourState::Alert,
+        (BehaviourState::Idle, _) => BehaviourState::Idle,
+        (BehaviourState::Alert, Some(distance)) if time_in_state >= config.reaction_time => {
+            engage(distance, 0.)
+        }
+        (BehaviourState::Alert, None) if time_in_state >= config.alert_duration => {
+            BehaviourState::Idle
+        }
+        (BehaviourState::Alert, _) => BehaviourState::Alert,
+        (BehaviourState::Chase, Some(distance)) => engage(distance, 0.),
+        (BehaviourState::Attack, Some(distance)) => engage(distance, ATTACK_RANGE_TOLERANCE),
+        // Keeps going to the last known position until forgets
+        (BehaviourState::Chase | BehaviourState::Attack, None) if senses.remembers_target => {
+            BehaviourState::Chase
+        }
+        (BehaviourState::Chase | BehaviourState::Attack | BehaviourState::Flee, _) => {
+            BehaviourState::Alert
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BehaviourConfig {
+        BehaviourConfig {
+            attack_range: 6.0,
+            reaction_time: Duration::from_millis(300),
+            alert_duration: Duration::from_secs(3),
+            flee_below_hp: 0.25,
+        }
+    }
+
+    fn sees(distance: f32) -> Senses {
+        Senses {
+            target_distance: Some(distance),
+            remembers_target: true,
+            hp: 1.0,
+        }
+    }
+
+    #[test]
+    fn reacts_after_reaction_time() {
+        let config = config();
+        let noticed = next_state(BehaviourState::Idle, Duration::ZERO, &sees(10.), &config);
+        assert_eq!(noticed, BehaviourState::Alert);
+
+        let state = next_state(noticed, Duration::from_millis(100), &sees(10.), &config);
+        assert_eq!(state, BehaviourState::Alert);
+        let state = next_state(noticed, Duration::from_millis(400), &sees(10.), &config);
+        assert_eq!(state, BehaviourState::Chase);
+        let state = next_state(state, Duration::ZERO, &sees(5.), &config);
+        assert_eq!(state, BehaviourState::Attack);
+        let state = next_state(state, Duration::ZERO, &sees(6.5), &config);
+        assert_eq!(state, BehaviourState::Attack);
+    }
+
+    #[test]
+    fn noise_alerts_idle_creature() {
+        let heard = Senses {
+            remembers_target: true,
+            hp: 1.0,
+            ..Default::default()
+        };
+
+        let state = next_state(BehaviourState::Idle, Duration::ZERO, &heard, &config());
+        assert_eq!(state, BehaviourState::Alert);
+    }
+
+    #[test]
+    fn forgets_lost_target() {
+        let config = config();
+        let mut senses = Senses {
+            remembers_target: true,
+            hp: 1.0,
+            ..Default::default()
+        };
+
+        let state = next_state(BehaviourState::Attack, Duration::ZERO, &senses, &config);
+        assert_eq!(state, BehaviourState::Chase);
+        senses.remembers_target = false;
+        let state = next_state(state, Duration::ZERO, &senses, &config);
+        assert_eq!(state, BehaviourState::Alert);
+        let state = next_state(state, Duration::from_secs(4), &senses, &config);
+        assert_eq!(state, BehaviourState::Idle);
+    }
+
+    #[test]
+    fn flees_with_low_hp() {
+        let config = config();
+        let senses = Senses {
+            hp: 0.2,
+            ..sees(3.)
+        };
+
+        let state = next_state(BehaviourState::Attack, Duration::ZERO, &senses, &config);
+        assert_eq!(state, BehaviourState::Flee);
+    }
+}
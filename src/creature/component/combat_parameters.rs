@@ -1,9 +1,20 @@
+use crate::audio::DamageSoundType;
+use crate::creature::component::attack::damage::DamageRoll;
 use bevy::prelude::*;
+use lib::entity::damage::DamageType;
+use lib::entity::weapon::MeleeParams;
+use std::time::Duration;
 
 #[derive(Component, Debug)]
 pub struct CombatParameters {
     pub attack_length: f32,
     pub base_damage: u16,
+    pub damage_type: DamageType,
+    /// From 0 to 1
+    pub crit_chance: f32,
+    /// Between the strikes of the player, creatures follow their attack set
+    pub cooldown: Duration,
+    pub sound_type: DamageSoundType,
 }
 
 impl Default for CombatParameters {
@@ -11,6 +22,32 @@ impl Default for CombatParameters {
         CombatParameters {
             attack_length: 1.5,
             base_damage: 10,
+            damage_type: DamageType::Physical,
+            crit_chance: 0.1,
+            cooldown: Duration::from_millis(300),
+            sound_type: DamageSoundType::Punch,
+        }
+    }
+}
+
+impl CombatParameters {
+    /// Strikes of the weapon, `sound_type` tells which weapon hits.
+    pub fn from_melee(params: &MeleeParams, sound_type: DamageSoundType) -> Self {
+        CombatParameters {
+            attack_length: params.reach,
+            base_damage: params.damage,
+            damage_type: params.damage_type,
+            crit_chance: params.crit_chance,
+            cooldown: Duration::from_secs_f32(params.cooldown.max(0.)),
+            sound_type,
+        }
+    }
+
+    pub fn damage(&self) -> DamageRoll {
+        DamageRoll {
+            base: self.base_damage,
+            damage_type: self.damage_type,
+            crit_chance: self.crit_chance,
         }
     }
 }
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use lib::entity::level::creature::CreatureRelation;
+
+/// Side of the entity in fights, decides who attacks whom.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Faction {
+    Player,
+    /// Ignores everybody until gets hit
+    Neutral,
+    /// On the side of the player, never hurt by them
+    Friend,
+    Enemy,
+}
+
+impl From<CreatureRelation> for Faction {
+    fn from(relation: CreatureRelation) -> Self {
+        match relation {
+            CreatureRelation::Neutral => Faction::Neutral,
+            CreatureRelation::Friend => Faction::Friend,
+            CreatureRelation::Enemy => Faction::Enemy,
+        }
+    }
+}
+
+impl Faction {
+    pub fn is_hostile_to(self, other: Faction) -> bool {
+        matches!(
+            (self, other),
+            (Faction::Enemy, Faction::Player | Faction::Friend)
+                | (Faction::Player | Faction::Friend, Faction::Enemy)
+        )
+    }
+
+    /// Whether the player's attacks hurt the entity.
+    pub fn can_be_hit_by_player(self) -> bool {
+        matches!(self, Faction::Neutral | Faction::Enemy)
+    }
+
+    /// Whether the attacks of the faction hurt the other one, the player hits neutrals too.
+    pub fn can_hurt(self, other: Faction) -> bool {
+        if self == Faction::Player {
+            other.can_be_hit_by_player()
+        } else {
+            self.is_hostile_to(other)
+        }
+    }
+
+    /// Neutrals turn on whoever may hurt them, hits of the level or of bystanders don't count.
+    pub fn is_provoked_by(self, source: Option<Faction>) -> bool {
+        self == Faction::Neutral && source.map_or(false, |source| source.can_hurt(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enemies_fight_player_and_friends() {
+        assert!(Faction::Enemy.is_hostile_to(Faction::Player));
+        assert!(Faction::Enemy.is_hostile_to(Faction::Friend));
+        assert!(Faction::Friend.is_hostile_to(Faction::Enemy));
+        assert!(!Faction::Enemy.is_hostile_to(Faction::Enemy));
+        assert!(!Faction::Neutral.is_hostile_to(Faction::Player));
+        assert!(!Faction::Friend.can_be_hit_by_player());
+        assert!(Faction::Player.can_hurt(Faction::Neutral));
+        assert!(!Faction::Player.can_hurt(Faction::Friend));
+        assert!(!Faction::Friend.can_hurt(Faction::Neutral));
+    }
+
+    #[test]
+    fn only_attackers_provoke_neutrals() {
+        assert!(Faction::Neutral.is_provoked_by(Some(Faction::Player)));
+        assert!(!Faction::Neutral.is_provoked_by(None));
+        assert!(!Faction::Neutral.is_provoked_by(Some(Faction::Enemy)));
+        assert!(!Faction::Enemy.is_provoked_by(Some(Faction::Player)));
+    }
+}
@@ -1,7 +1,11 @@
-use crate::creature::EnemyCreatureMarker;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use crate::creature::CreatureMarker;
 use bevy::prelude::*;
 use std::cmp;
 
+// Gap between the top of the body and the HP bar
+const HP_BAR_MARGIN: f32 = 0.5;
+
 #[derive(Component, Debug)]
 pub struct HPMeshMarker;
 
@@ -32,13 +36,24 @@ impl HP {
     pub fn sub(&mut self, amount: u16) {
         self.current -= cmp::min(self.current, amount);
     }
+
+    /// Returns how much was actually restored.
+    pub fn heal(&mut self, amount: u16) -> u16 {
+        let healed = cmp::min(self.max - self.current, amount);
+        self.current += healed;
+        healed
+    }
+
+    pub fn restore(&mut self) {
+        self.current = self.max;
+    }
 }
 
 pub fn creature_hp_spawn_mesh(
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut commands: Commands,
-    creatures: Query<Entity, (With<EnemyCreatureMarker>, Added<HP>)>,
+    creatures: Query<(Entity, &PhysiologyDescription), (With<CreatureMarker>, Added<HP>)>,
 ) {
     let black_hp_material = materials.add(StandardMaterial {
         base_color: Color::BLACK,
@@ -53,20 +68,21 @@ pub fn creature_hp_spawn_mesh(
     let red_hp_mesh = meshes.add(Mesh::from(shape::Box::new(2.01, 0.51, 0.51)));
     let black_hp_mesh = meshes.add(Mesh::from(shape::Box::new(2.0, 0.5, 0.5)));
 
-    for creature in creatures.iter() {
+    for (creature, phys) in creatures.iter() {
+        let height = phys.model_height / 2. + HP_BAR_MARGIN;
         commands.entity(creature).with_children(|builder| {
             builder
                 .spawn_bundle(PbrBundle {
                     mesh: red_hp_mesh.clone(),
                     material: red_hp_material.clone(),
-                    transform: Transform::from_xyz(0., 4.5, 0.),
+                    transform: Transform::from_xyz(0., height, 0.),
                     ..Default::default()
                 })
                 .insert(HPMeshMarker);
             builder.spawn_bundle(PbrBundle {
                 mesh: black_hp_mesh.clone(),
                 material: black_hp_material.clone(),
-                transform: Transform::from_xyz(0., 4.5, 0.),
+                transform: Transform::from_xyz(0., height, 0.),
                 ..Default::default()
             });
         });
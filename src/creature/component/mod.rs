@@ -1,6 +1,9 @@
 pub mod attack;
+pub mod behaviour;
 mod combat_parameters;
+pub mod faction;
 pub mod movement;
+pub mod perception;
 pub mod physiology_description;
 pub mod hp;
 
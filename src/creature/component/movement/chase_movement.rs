@@ -0,0 +1,160 @@
+use crate::creature::component::movement::locomotivity;
+use crate::creature::component::movement::path::PathFollower;
+use crate::creature::component::movement::MoveYourBody;
+use crate::creature::component::perception::Perception;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::math::vec3;
+use bevy::prelude::*;
+use heron::Velocity;
+use lib::entity::level::navigation::Pathfinder;
+use lib::entity::level::Level;
+
+// Creature doesn't fidget around the preferred distance
+const DISTANCE_TOLERANCE: f32 = 1.0;
+const HOME_RADIUS: f32 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct ChaseConfig {
+    /// 0 for melee creatures, ranged ones stop and back off to keep it
+    pub preferred_distance: f32,
+}
+
+/// Goes to the last known position of the target and returns home when the creature forgets it.
+#[derive(Debug)]
+pub struct ChaseStrategy {
+    config: ChaseConfig,
+    home: Option<Vec3>,
+    path: PathFollower,
+}
+
+/// Where the creature heads in this frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChaseGoal {
+    /// Keeps the preferred distance or is already at home
+    Stay,
+    /// Direction away from the target which came too close
+    BackOff(Vec3),
+    /// The target, the place it was last perceived at or home
+    Follow(Vec3),
+}
+
+impl ChaseStrategy {
+    pub fn new(config: ChaseConfig) -> Self {
+        ChaseStrategy {
+            config,
+            home: None,
+            path: PathFollower::default(),
+        }
+    }
+
+    fn goal(
+        &mut self,
+        position: Vec3,
+        visible_target: Option<Vec3>,
+        last_known_target: Option<Vec3>,
+    ) -> ChaseGoal {
+        let home = *self.home.get_or_insert(position);
+
+        if let Some(target) = last_known_target {
+            let to_target = vec3(target.x - position.x, 0., target.z - position.z);
+            let distance = to_target.length();
+            let preferred = self.config.preferred_distance;
+
+            if visible_target.is_some() && distance < preferred - DISTANCE_TOLERANCE {
+                ChaseGoal::BackOff(-to_target.normalize_or_zero())
+            } else if visible_target.is_some() && distance <= preferred {
+                ChaseGoal::Stay
+            } else {
+                ChaseGoal::Follow(target)
+            }
+        } else if home.distance(position) > HOME_RADIUS {
+            ChaseGoal::Follow(home)
+        } else {
+            ChaseGoal::Stay
+        }
+    }
+}
+
+impl MoveYourBody for ChaseStrategy {
+    fn update(
+        &mut self,
+        phys: &PhysiologyDescription,
+        lvl: &Res<Level>,
+        pathfinder: &Pathfinder,
+        perception: &Perception,
+        transform: &Transform,
+        velocity: &mut Velocity,
+    ) {
+        let position = transform.translation;
+        let goal = self.goal(
+            position,
+            perception.visible_target(),
+            perception.last_known_target(),
+        );
+
+        let direction = match goal {
+            ChaseGoal::Stay => None,
+            ChaseGoal::BackOff(away) => {
+                locomotivity::can_walk_towards(lvl, phys, position, away).then_some(away)
+            }
+            ChaseGoal::Follow(target) => {
+                self.path.direction(pathfinder, lvl, phys, position, target)
+            }
+        };
+
+        let direction = direction.unwrap_or(Vec3::ZERO);
+        *velocity = velocity.with_linear(vec3(
+            direction.x * phys.movement_speed,
+            velocity.linear.y,
+            direction.z * phys.movement_speed,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chase(preferred_distance: f32) -> ChaseStrategy {
+        ChaseStrategy::new(ChaseConfig { preferred_distance })
+    }
+
+    #[test]
+    fn ranged_creature_keeps_its_distance() {
+        let mut strategy = chase(5.);
+        let position = vec3(0., 1., 0.);
+        let target = |x| Some(vec3(x, 1., 0.));
+
+        assert_eq!(
+            strategy.goal(position, target(4.5), target(4.5)),
+            ChaseGoal::Stay
+        );
+        assert_eq!(
+            strategy.goal(position, target(2.), target(2.)),
+            ChaseGoal::BackOff(Vec3::NEG_X)
+        );
+        assert_eq!(
+            strategy.goal(position, target(8.), target(8.)),
+            ChaseGoal::Follow(vec3(8., 1., 0.))
+        );
+    }
+
+    #[test]
+    fn lost_target_is_searched_then_creature_returns_home() {
+        let mut strategy = chase(0.);
+        let home = vec3(0., 1., 0.);
+        let last_seen = vec3(6., 1., 2.);
+        assert_eq!(strategy.goal(home, None, None), ChaseGoal::Stay);
+
+        let position = vec3(3., 1., 1.);
+        assert_eq!(
+            strategy.goal(position, None, Some(last_seen)),
+            ChaseGoal::Follow(last_seen)
+        );
+        assert_eq!(
+            strategy.goal(last_seen, None, None),
+            ChaseGoal::Follow(home)
+        );
+        assert_eq!(strategy.goal(home, None, None), ChaseGoal::Stay);
+    }
+}
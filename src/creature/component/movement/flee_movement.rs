@@ -0,0 +1,56 @@
+use crate::creature::component::movement::locomotivity;
+use crate::creature::component::movement::MoveYourBody;
+use crate::creature::component::perception::Perception;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::math::vec3;
+use bevy::prelude::*;
+use heron::Velocity;
+use lib::entity::level::navigation::Pathfinder;
+use lib::entity::level::Level;
+use std::f32::consts::FRAC_PI_2;
+
+#[derive(Debug)]
+pub struct FleeStrategy {
+    safe_distance: f32,
+}
+
+impl FleeStrategy {
+    pub fn new(safe_distance: f32) -> Self {
+        FleeStrategy { safe_distance }
+    }
+}
+
+impl MoveYourBody for FleeStrategy {
+    fn update(
+        &mut self,
+        phys: &PhysiologyDescription,
+        lvl: &Res<Level>,
+        _pathfinder: &Pathfinder,
+        perception: &Perception,
+        transform: &Transform,
+        velocity: &mut Velocity,
+    ) {
+        let position = transform.translation;
+        let direction = perception
+            .last_known_target()
+            .map(|threat| vec3(position.x - threat.x, 0., position.z - threat.z))
+            .filter(|away| away.length() < self.safe_distance)
+            .and_then(|away| {
+                let away = away.normalize_or_zero();
+                // Runs along the wall when cornered
+                [0., FRAC_PI_2, -FRAC_PI_2]
+                    .into_iter()
+                    .map(|angle| Quat::from_rotation_y(angle) * away)
+                    .find(|direction| {
+                        locomotivity::can_walk_towards(lvl, phys, position, *direction)
+                    })
+            })
+            .unwrap_or(Vec3::ZERO);
+
+        *velocity = velocity.with_linear(vec3(
+            direction.x * phys.movement_speed,
+            velocity.linear.y,
+            direction.z * phys.movement_speed,
+        ));
+    }
+}
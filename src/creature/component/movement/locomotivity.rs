@@ -1,31 +1,105 @@
-use bevy::math::vec3;
 use crate::creature::component::physiology_description::PhysiologyDescription;
+use crate::GRAVITY;
+use bevy::math::vec3;
 use bevy::prelude::*;
+use heron::Velocity;
 use lib::entity::level::Level;
-use lib::util::math::round_based;
+use lib::util::math::{touched_points, voxel_at};
 
-pub fn creature_not_inside_blocks(
-    x: f32,
-    y: f32,
-    z: f32,
-    lvl: &Res<Level>,
+// How far in front of the body obstacles are looked for
+const PROBE_DISTANCE: f32 = 0.3;
+// The body has to clear the edge, not just touch it
+const JUMP_MARGIN: f32 = 0.25;
+// Slower vertical movement counts as standing on the floor
+const GROUNDED_SPEED: f32 = 0.5;
+
+/// What is in front of the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ahead {
+    Free,
+    /// A ledge of this many voxels the creature can jump on
+    Step(i32),
+    Wall,
+}
+
+pub fn look_ahead(
+    lvl: &Level,
+    phys: &PhysiologyDescription,
+    position: Vec3,
+    direction: Vec3,
+) -> Ahead {
+    let direction = if let Some(direction) = vec3(direction.x, 0., direction.z).try_normalize() {
+        direction
+    } else {
+        return Ahead::Free;
+    };
+    let ahead = position + direction * (phys.model_radius + PROBE_DISTANCE);
+    let feet = feet_level(phys, position);
+    let height = (phys.model_height.ceil() as i32).max(1);
+    let is_solid = |point: Vec3, y: i32| {
+        lvl.get_voxel_by_point(&voxel_at(vec3(point.x, y as f32, point.z)).as_vec3())
+            .is_some()
+    };
+
+    if (0..height).all(|dy| !is_solid(ahead, feet + dy)) {
+        return Ahead::Free;
+    }
+
+    let max_step = phys.jump_height.floor() as i32;
+    (1..=max_step)
+        .find(|step| {
+            let fits = (0..height).all(|dy| !is_solid(ahead, feet + step + dy));
+            let headroom = (height..height + step).all(|dy| !is_solid(position, feet + dy));
+            fits && headroom
+        })
+        .map_or(Ahead::Wall, Ahead::Step)
+}
+
+/// The body can go in the direction, maybe with a jump.
+pub fn can_walk_towards(
+    lvl: &Level,
     phys: &PhysiologyDescription,
+    position: Vec3,
+    direction: Vec3,
 ) -> bool {
-    let mut obstacles: Vec<Vec3> = get_touched_points(x, y, z, phys);
+    look_ahead(lvl, phys, position, direction) != Ahead::Wall
+}
 
-    obstacles = obstacles
-        .iter()
-        .map(|p| vec3(p.x, p.y + 1.0, p.z))
-        .collect();
+/// Jumps on the step in the way of the horizontal velocity.
+pub fn step_up(lvl: &Level, phys: &PhysiologyDescription, position: Vec3, velocity: &mut Velocity) {
+    if !is_grounded(lvl, phys, position, velocity) {
+        return;
+    }
 
-    obstacles.extend(
-        obstacles
-            .iter()
-            .map(|p| vec3(p.x, p.y + 1.0, p.z))
-            .collect::<Vec<Vec3>>(),
-    );
+    if let Ahead::Step(_) = look_ahead(lvl, phys, position, velocity.linear) {
+        velocity.linear.y = jump_speed(phys);
+    }
+}
 
-    lvl.points_are_empty(&obstacles)
+/// Vertical speed which lifts the body by its jump height.
+pub fn jump_speed(phys: &PhysiologyDescription) -> f32 {
+    (2. * GRAVITY * (phys.jump_height + JUMP_MARGIN)).sqrt()
+}
+
+fn is_grounded(
+    lvl: &Level,
+    phys: &PhysiologyDescription,
+    position: Vec3,
+    velocity: &Velocity,
+) -> bool {
+    let floor = voxel_at(vec3(
+        position.x,
+        (feet_level(phys, position) - 1) as f32,
+        position.z,
+    ))
+    .as_vec3();
+
+    velocity.linear.y.abs() < GROUNDED_SPEED && lvl.get_voxel_by_point(&floor).is_some()
+}
+
+// Voxel level the feet stand in, the position is the center of the body
+fn feet_level(phys: &PhysiologyDescription, position: Vec3) -> i32 {
+    (position.y - phys.model_height / 2.).round() as i32
 }
 
 #[allow(dead_code)]
@@ -49,48 +123,59 @@ pub fn has_y_obstacles_on_point(
 }
 
 fn get_touched_points(x: f32, y: f32, z: f32, phys: &PhysiologyDescription) -> Vec<Vec3> {
-    let model_radius = phys.model_radius;
-    let mut points: Vec<Vec3> = vec![vec3(x.floor(), y, z), vec3(x, y, z.floor())];
-
-    let x_gap = round_based(x - x.floor(), 1);
-    if x_gap > model_radius {
-        points.push(vec3((x + model_radius).floor(), y, z.floor()));
-    } else if x_gap < model_radius {
-        points.push(vec3((x - model_radius).floor(), y, z.floor()));
-    };
+    touched_points(x, y, z, phys.model_radius)
+}
 
-    let z_gap = round_based(z - z.floor(), 1);
-    if z_gap > model_radius {
-        points.push(vec3(x.floor(), y, (z + model_radius).floor()));
-    } else if z_gap < model_radius {
-        points.push(vec3(x.floor(), y, (z - model_radius).floor()));
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib::entity::level::DayPart;
+    use lib::entity::voxel::{Material, Shape, Voxel};
+
+    // Floor at y = 0 and a wall of the height at x = 5
+    fn level_with_wall(wall_height: usize) -> Level {
+        let mut voxels = vec![];
+        for x in 0..10 {
+            for z in 0..10 {
+                voxels.push(Voxel::new(
+                    vec3(x as f32, 0., z as f32),
+                    Material::Solid,
+                    Shape::Cube,
+                ));
+            }
+        }
+        for z in 0..10 {
+            for y in 1..=wall_height {
+                voxels.push(Voxel::new(
+                    vec3(5., y as f32, z as f32),
+                    Material::Solid,
+                    Shape::Cube,
+                ));
+            }
+        }
 
-    if x_gap > model_radius && z_gap > model_radius {
-        points.push(vec3(
-            (x + model_radius).floor(),
-            y,
-            (z + model_radius).floor(),
-        ));
-    } else if x_gap < model_radius && z_gap < model_radius {
-        points.push(vec3(
-            (x - model_radius).floor(),
-            y,
-            (z - model_radius).floor(),
-        ));
-    } else if x_gap > model_radius && z_gap < model_radius {
-        points.push(vec3(
-            (x + model_radius).floor(),
-            y,
-            (z - model_radius).floor(),
-        ));
-    } else if x_gap < model_radius && z_gap > model_radius {
-        points.push(vec3(
-            (x - model_radius).floor(),
-            y,
-            (z + model_radius).floor(),
-        ));
+        Level::new("test".to_string(), voxels, DayPart::Day, vec![])
     }
 
-    points
+    #[test]
+    fn jumps_on_low_ledge_only() {
+        let phys = PhysiologyDescription::default();
+        let near_wall = vec3(4.5, 2., 2.5);
+
+        let low = level_with_wall(1);
+        assert_eq!(look_ahead(&low, &phys, near_wall, Vec3::X), Ahead::Step(1));
+        assert_eq!(look_ahead(&low, &phys, near_wall, -Vec3::X), Ahead::Free);
+
+        let high = level_with_wall(2);
+        assert_eq!(look_ahead(&high, &phys, near_wall, Vec3::X), Ahead::Wall);
+        assert!(!can_walk_towards(&high, &phys, near_wall, Vec3::X));
+    }
+
+    #[test]
+    fn jump_reaches_jump_height() {
+        let phys = PhysiologyDescription::default();
+        let speed = jump_speed(&phys);
+
+        assert!(speed * speed / (2. * GRAVITY) > phys.jump_height);
+    }
 }
@@ -1,17 +1,34 @@
+use crate::creature::component::movement::chase_movement::{ChaseConfig, ChaseStrategy};
+use crate::creature::component::movement::flee_movement::FleeStrategy;
+use crate::creature::component::movement::patrol_movement::PatrolStrategy;
 use crate::creature::component::movement::random_movement::RandomMovementStrategy;
+use crate::creature::component::movement::stand_movement::StandStrategy;
+use crate::creature::component::perception::Perception;
 use crate::creature::component::physiology_description::PhysiologyDescription;
 use bevy::prelude::*;
 use heron::Velocity;
+use lib::entity::level::creature::PatrolRoute;
+use lib::entity::level::navigation::Pathfinder;
 use lib::entity::level::Level;
 use std::fmt::Debug;
+pub mod chase_movement;
+pub mod flee_movement;
 pub mod locomotivity;
+pub mod path;
+pub mod patrol_movement;
 pub mod random_movement;
+pub mod stand_movement;
+pub mod steering;
 
 pub trait MoveYourBody: Send + Sync + Debug {
+    /// `perception` tells what the creature knows about its targets.
+    #[allow(clippy::too_many_arguments)]
     fn update(
         &mut self,
         phys: &PhysiologyDescription,
         lvl: &Res<Level>,
+        pathfinder: &Pathfinder,
+        perception: &Perception,
         transform: &Transform,
         velocity: &mut Velocity,
     );
@@ -23,20 +40,46 @@ pub struct MovementStrategy {
 }
 
 impl MovementStrategy {
-    #[allow(dead_code)]
     pub fn random() -> MovementStrategy {
         MovementStrategy {
             strategy: Box::new(RandomMovementStrategy::new()),
         }
     }
 
+    pub fn stand() -> MovementStrategy {
+        MovementStrategy {
+            strategy: Box::new(StandStrategy),
+        }
+    }
+
+    pub fn chase(config: ChaseConfig) -> MovementStrategy {
+        MovementStrategy {
+            strategy: Box::new(ChaseStrategy::new(config)),
+        }
+    }
+
+    pub fn flee(safe_distance: f32) -> MovementStrategy {
+        MovementStrategy {
+            strategy: Box::new(FleeStrategy::new(safe_distance)),
+        }
+    }
+
+    pub fn patrol(route: PatrolRoute) -> MovementStrategy {
+        MovementStrategy {
+            strategy: Box::new(PatrolStrategy::new(route)),
+        }
+    }
+
     pub fn update(
         &mut self,
         phys: &PhysiologyDescription,
         lvl: &Res<Level>,
+        pathfinder: &Pathfinder,
+        perception: &Perception,
         transform: &Transform,
         velocity: &mut Velocity,
     ) {
-        self.strategy.update(phys, lvl, transform, velocity);
+        self.strategy
+            .update(phys, lvl, pathfinder, perception, transform, velocity);
     }
 }
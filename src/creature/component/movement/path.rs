@@ -0,0 +1,66 @@
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::math::{vec2, vec3};
+use bevy::prelude::*;
+use lib::entity::level::navigation::Pathfinder;
+use lib::entity::level::Level;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// The path is rebuilt when the target moves further than this
+const REPLAN_DISTANCE: f32 = 1.5;
+const REPLAN_PERIOD: Duration = Duration::from_millis(500);
+const WAYPOINT_RADIUS: f32 = 0.3;
+
+/// Keeps a path to the target and tells in which direction to go to follow it.
+#[derive(Debug, Default)]
+pub struct PathFollower {
+    waypoints: VecDeque<Vec3>,
+    target: Option<Vec3>,
+    planned_at: Option<Instant>,
+}
+
+impl PathFollower {
+    /// Normalized horizontal direction, `None` if the target is reached or unreachable.
+    pub fn direction(
+        &mut self,
+        pathfinder: &Pathfinder,
+        lvl: &Level,
+        phys: &PhysiologyDescription,
+        position: Vec3,
+        target: Vec3,
+    ) -> Option<Vec3> {
+        if self.should_replan(target) {
+            self.waypoints = pathfinder
+                .find_path(lvl, &phys.body_size(), position, target)
+                .map(VecDeque::from)
+                .unwrap_or_default();
+            self.target = Some(target);
+            self.planned_at = Some(Instant::now());
+        }
+
+        while let Some(waypoint) = self.waypoints.front() {
+            let diff = *waypoint - position;
+            if vec2(diff.x, diff.z).length() > WAYPOINT_RADIUS {
+                return Some(vec3(diff.x, 0., diff.z).normalize());
+            }
+            self.waypoints.pop_front();
+        }
+
+        None
+    }
+
+    pub fn reset(&mut self) {
+        *self = PathFollower::default();
+    }
+
+    fn should_replan(&self, target: Vec3) -> bool {
+        let target_moved = self
+            .target
+            .map_or(true, |old| old.distance(target) > REPLAN_DISTANCE);
+        let outdated = self
+            .planned_at
+            .map_or(true, |time| time.elapsed() > REPLAN_PERIOD);
+
+        target_moved || (outdated && self.waypoints.is_empty())
+    }
+}
@@ -0,0 +1,136 @@
+use crate::creature::component::movement::path::PathFollower;
+use crate::creature::component::movement::MoveYourBody;
+use crate::creature::component::perception::Perception;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::math::{vec2, vec3};
+use bevy::prelude::*;
+use heron::Velocity;
+use lib::entity::level::creature::{PatrolMode, PatrolRoute};
+use lib::entity::level::navigation::Pathfinder;
+use lib::entity::level::Level;
+use std::time::{Duration, Instant};
+
+const WAYPOINT_RADIUS: f32 = 0.5;
+
+#[derive(Debug)]
+pub struct PatrolStrategy {
+    route: PatrolRoute,
+    current: usize,
+    forward: bool,
+    waiting_since: Option<Instant>,
+    path: PathFollower,
+}
+
+impl PatrolStrategy {
+    pub fn new(route: PatrolRoute) -> Self {
+        PatrolStrategy {
+            route,
+            current: 0,
+            forward: true,
+            waiting_since: None,
+            path: PathFollower::default(),
+        }
+    }
+
+    fn next_waypoint(&mut self) {
+        let last = self.route.waypoints.len() - 1;
+        self.current = match self.route.mode {
+            PatrolMode::Loop => (self.current + 1) % (last + 1),
+            PatrolMode::PingPong => {
+                if self.current == last {
+                    self.forward = false;
+                } else if self.current == 0 {
+                    self.forward = true;
+                }
+                if self.forward {
+                    (self.current + 1).min(last)
+                } else {
+                    self.current.saturating_sub(1)
+                }
+            }
+        };
+        self.path.reset();
+    }
+}
+
+impl MoveYourBody for PatrolStrategy {
+    fn update(
+        &mut self,
+        phys: &PhysiologyDescription,
+        lvl: &Res<Level>,
+        pathfinder: &Pathfinder,
+        _perception: &Perception,
+        transform: &Transform,
+        velocity: &mut Velocity,
+    ) {
+        let position = transform.translation;
+        let direction = if let Some(waypoint) = self.route.waypoints.get(self.current).copied() {
+            let diff = waypoint.position - position;
+            if vec2(diff.x, diff.z).length() > WAYPOINT_RADIUS {
+                self.path
+                    .direction(pathfinder, lvl, phys, position, waypoint.position)
+            } else {
+                let waiting_since = *self.waiting_since.get_or_insert_with(Instant::now);
+                if waiting_since.elapsed() >= Duration::from_secs_f32(waypoint.wait.max(0.)) {
+                    self.waiting_since = None;
+                    self.next_waypoint();
+                }
+                None
+            }
+        } else {
+            None
+        };
+
+        let direction = direction.unwrap_or(Vec3::ZERO);
+        *velocity = velocity.with_linear(vec3(
+            direction.x * phys.movement_speed,
+            velocity.linear.y,
+            direction.z * phys.movement_speed,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib::entity::level::creature::Waypoint;
+
+    fn patrol(points: usize, mode: PatrolMode) -> PatrolStrategy {
+        let waypoints = (0..points)
+            .map(|x| Waypoint {
+                position: vec3(x as f32, 1., 0.),
+                wait: 0.,
+            })
+            .collect();
+        PatrolStrategy::new(PatrolRoute { waypoints, mode })
+    }
+
+    fn visited(strategy: &mut PatrolStrategy, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                strategy.next_waypoint();
+                strategy.current
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loop_wraps_to_the_first_waypoint() {
+        let mut strategy = patrol(3, PatrolMode::Loop);
+        assert_eq!(visited(&mut strategy, 5), vec![1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn ping_pong_turns_at_the_ends() {
+        let mut strategy = patrol(3, PatrolMode::PingPong);
+        assert_eq!(visited(&mut strategy, 6), vec![1, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn single_waypoint_route_stays_put() {
+        let mut strategy = patrol(1, PatrolMode::Loop);
+        assert_eq!(visited(&mut strategy, 2), vec![0, 0]);
+        let mut strategy = patrol(1, PatrolMode::PingPong);
+        assert_eq!(visited(&mut strategy, 2), vec![0, 0]);
+    }
+}
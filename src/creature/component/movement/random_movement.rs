@@ -1,9 +1,11 @@
 use crate::creature::component::movement::locomotivity;
 use crate::creature::component::movement::MoveYourBody;
+use crate::creature::component::perception::Perception;
 use crate::creature::component::physiology_description::PhysiologyDescription;
 use bevy::math::vec3;
 use bevy::prelude::*;
 use heron::Velocity;
+use lib::entity::level::navigation::Pathfinder;
 use lib::entity::level::Level;
 use rand::Rng;
 
@@ -24,54 +26,44 @@ impl MoveYourBody for RandomMovementStrategy {
         &mut self,
         phys: &PhysiologyDescription,
         lvl: &Res<Level>,
+        _pathfinder: &Pathfinder,
+        _perception: &Perception,
         transform: &Transform,
         velocity: &mut Velocity,
     ) {
-        let x = transform.translation.x;
-        let y = transform.translation.y;
-        let z = transform.translation.z;
-        let distance = 0.5;
-        let potential_positions = [
-            (x, z),
-            (x + distance, z),
-            (x, z + distance),
-            (x + distance, z + distance),
-            (x - distance, z),
-            (x, z - distance),
-            (x - distance, z - distance),
-            (x - distance, z + distance),
-            (x + distance, z - distance),
+        let position = transform.translation;
+        let potential_directions = [
+            Vec3::ZERO,
+            vec3(1., 0., 0.),
+            vec3(0., 0., 1.),
+            vec3(1., 0., 1.),
+            vec3(-1., 0., 0.),
+            vec3(0., 0., -1.),
+            vec3(-1., 0., -1.),
+            vec3(-1., 0., 1.),
+            vec3(1., 0., -1.),
         ];
-        let valid_positions: Vec<(f32, f32)> = potential_positions
-            .into_iter()
-            .filter(|(x, z)| {
-                locomotivity::creature_not_inside_blocks(*x, y, *z, lvl, phys)
-                    // && locomotivity::has_y_obstacles_on_point(*x, y, *z, lvl, phys)
-            })
-            .collect();
+        // Low ledges are jumped on, see `locomotivity::step_up`
+        let is_valid =
+            |direction: Vec3| locomotivity::can_walk_towards(lvl, phys, position, direction);
 
-        if valid_positions.is_empty() {
-            return;
-        }
-
-        let index = if self.i < 20 && valid_positions.get(self.direction).is_some() {
-            self.direction
+        let direction = if self.i < 20 && is_valid(potential_directions[self.direction]) {
+            potential_directions[self.direction]
         } else {
+            let valid_directions: Vec<usize> = (0..potential_directions.len())
+                .filter(|index| is_valid(potential_directions[*index]))
+                .collect();
             let mut rng = rand::thread_rng();
-            let index = rng.gen_range(0..valid_positions.len());
             self.i = 0;
-            self.direction = index;
-            index
+            self.direction = valid_directions[rng.gen_range(0..valid_directions.len())];
+            potential_directions[self.direction]
         };
-        // dbg!(potential_positions);
 
-        let (new_x, new_z) = potential_positions[index];
-        let diff_x = transform.translation.x - new_x;
-        let diff_z = transform.translation.z - new_z;
+        let direction = direction.normalize_or_zero() * 0.5;
         *velocity = velocity.with_linear(vec3(
-            diff_x * phys.movement_speed,
+            direction.x * phys.movement_speed,
             velocity.linear.y,
-            diff_z * phys.movement_speed,
+            direction.z * phys.movement_speed,
         ));
 
         self.i += 1;
@@ -0,0 +1,25 @@
+use crate::creature::component::movement::MoveYourBody;
+use crate::creature::component::perception::Perception;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::math::vec3;
+use bevy::prelude::*;
+use heron::Velocity;
+use lib::entity::level::navigation::Pathfinder;
+use lib::entity::level::Level;
+
+#[derive(Debug)]
+pub struct StandStrategy;
+
+impl MoveYourBody for StandStrategy {
+    fn update(
+        &mut self,
+        _phys: &PhysiologyDescription,
+        _lvl: &Res<Level>,
+        _pathfinder: &Pathfinder,
+        _perception: &Perception,
+        _transform: &Transform,
+        velocity: &mut Velocity,
+    ) {
+        *velocity = velocity.with_linear(vec3(0., velocity.linear.y, 0.));
+    }
+}
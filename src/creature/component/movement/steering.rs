@@ -0,0 +1,193 @@
+use crate::creature::component::movement::locomotivity;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::math::vec3;
+use bevy::prelude::*;
+use lib::entity::level::Level;
+use std::collections::HashMap;
+
+// Neighbours further than this don't affect the steering, also the size of the index cells
+const NEIGHBOUR_RADIUS: f32 = 3.0;
+// Gap creatures keep between their bodies
+const PERSONAL_SPACE: f32 = 0.5;
+const SEPARATION_WEIGHT: f32 = 1.0;
+const ALIGNMENT_WEIGHT: f32 = 0.3;
+// Degrees, tried one by one when the steered direction runs into a wall
+const AVOIDANCE_ANGLES: [f32; 4] = [45., -45., 90., -90.];
+
+/// A creature as its neighbours see it.
+#[derive(Debug, Clone, Copy)]
+pub struct Neighbour {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub radius: f32,
+}
+
+/// Creatures bucketed by horizontal grid cells, so only the close ones are checked.
+/// Rebuilt every frame before the creatures move.
+#[derive(Debug)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<Neighbour>>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        SpatialIndex::new(NEIGHBOUR_RADIUS)
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialIndex {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Keeps the cells allocated for the next frame.
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, neighbour: Neighbour) {
+        let cell = self.cell(neighbour.position);
+        self.cells.entry(cell).or_default().push(neighbour);
+    }
+
+    /// Everything within the horizontal `radius` around the position.
+    pub fn near(&self, position: Vec3, radius: f32) -> impl Iterator<Item = &Neighbour> {
+        let (min_x, min_z) = self.cell(position - Vec3::splat(radius));
+        let (max_x, max_z) = self.cell(position + Vec3::splat(radius));
+        (min_x..=max_x)
+            .flat_map(move |x| (min_z..=max_z).map(move |z| (x, z)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |neighbour| horizontal(neighbour.position - position).length() <= radius)
+    }
+
+    fn cell(&self, position: Vec3) -> (i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32,
+        )
+    }
+}
+
+/// Blends the velocity the strategy wants with keeping apart from the neighbours
+/// and moving along with them. The vertical speed is left to the strategy.
+pub fn steer(body: &Neighbour, desired: Vec3, max_speed: f32, index: &SpatialIndex) -> Vec3 {
+    let wanted = horizontal(desired);
+    let mut separation = Vec3::ZERO;
+    let mut heading = Vec3::ZERO;
+    let mut moving = 0;
+
+    for other in index
+        .near(body.position, NEIGHBOUR_RADIUS)
+        .filter(|other| other.entity != body.entity)
+    {
+        let away = horizontal(body.position - other.position);
+        let distance = away.length();
+        let space = body.radius + other.radius + PERSONAL_SPACE;
+        if distance < space {
+            // Bodies at the same point go opposite ways
+            let fallback = if body.entity > other.entity {
+                Vec3::X
+            } else {
+                Vec3::NEG_X
+            };
+            let direction = away.try_normalize().unwrap_or(fallback);
+            separation += direction * (1. - distance / space);
+        }
+
+        let velocity = horizontal(other.velocity);
+        if velocity != Vec3::ZERO {
+            heading += velocity;
+            moving += 1;
+        }
+    }
+
+    let mut steered = wanted + separation * SEPARATION_WEIGHT * max_speed;
+    if moving > 0 && wanted != Vec3::ZERO {
+        steered += (heading / moving as f32 - wanted) * ALIGNMENT_WEIGHT;
+    }
+    let steered = steered.clamp_length_max(max_speed);
+    vec3(steered.x, desired.y, steered.z)
+}
+
+/// Turns the steered velocity away from walls, falls back to what the strategy wanted.
+pub fn avoid_obstacles(
+    lvl: &Level,
+    phys: &PhysiologyDescription,
+    position: Vec3,
+    steered: Vec3,
+    desired: Vec3,
+) -> Vec3 {
+    let is_walkable = |velocity: Vec3| {
+        horizontal(velocity) == Vec3::ZERO
+            || locomotivity::can_walk_towards(lvl, phys, position, velocity)
+    };
+    if is_walkable(steered) {
+        return steered;
+    }
+
+    AVOIDANCE_ANGLES
+        .iter()
+        .map(|angle| Quat::from_rotation_y(angle.to_radians()) * steered)
+        .find(|velocity| is_walkable(*velocity))
+        .unwrap_or(desired)
+}
+
+fn horizontal(vector: Vec3) -> Vec3 {
+    vec3(vector.x, 0., vector.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn neighbour(id: u32, x: f32, z: f32) -> Neighbour {
+        Neighbour {
+            entity: Entity::from_raw(id),
+            position: vec3(x, 0., z),
+            velocity: Vec3::ZERO,
+            radius: 0.5,
+        }
+    }
+
+    #[test]
+    fn index_finds_only_close_creatures() {
+        let mut index = SpatialIndex::new(2.);
+        index.insert(neighbour(0, 0., 0.));
+        index.insert(neighbour(1, 1.5, -1.));
+        index.insert(neighbour(2, 10., 0.));
+
+        let mut near: Vec<u32> = index
+            .near(Vec3::ZERO, 3.)
+            .map(|neighbour| neighbour.entity.id())
+            .collect();
+        near.sort_unstable();
+        assert_eq!(near, vec![0, 1]);
+
+        index.clear();
+        assert_eq!(index.near(Vec3::ZERO, 3.).count(), 0);
+    }
+
+    #[test]
+    fn crowded_creatures_are_pushed_apart() {
+        let mut index = SpatialIndex::default();
+        let left = neighbour(0, -0.5, 0.);
+        let right = neighbour(1, 0.5, 0.);
+        index.insert(left);
+        index.insert(right);
+
+        let desired = vec3(0., -1., 2.);
+        let steered = steer(&left, desired, 5., &index);
+        assert!(steered.x < 0.);
+        assert!(steered.z > 0.);
+        assert_eq!(steered.y, desired.y);
+        assert!(steer(&right, Vec3::ZERO, 5., &index).x > 0.);
+    }
+}
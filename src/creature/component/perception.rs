@@ -0,0 +1,294 @@
+use crate::creature::component::faction::Faction;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use bevy::math::vec3;
+use bevy::prelude::*;
+use heron::rapier_plugin::PhysicsWorld;
+use std::time::{Duration, Instant};
+
+// Anything closer is noticed even behind the back
+const CLOSE_RANGE: f32 = 2.0;
+
+#[derive(Debug, Clone)]
+pub struct PerceptionConfig {
+    pub sight_range: f32,
+    /// Sight range when the target isn't lit
+    pub dark_sight_range: f32,
+    /// Full angle of the sight cone in radians
+    pub field_of_view: f32,
+    pub hearing_range: f32,
+    /// How long the last known position of the target stays relevant
+    pub memory: Duration,
+}
+
+impl Default for PerceptionConfig {
+    fn default() -> Self {
+        PerceptionConfig {
+            sight_range: 15.0,
+            dark_sight_range: 6.0,
+            field_of_view: 120_f32.to_radians(),
+            hearing_range: 10.0,
+            memory: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stimulus {
+    Sight,
+    Hearing,
+}
+
+/// Someone hostile the creature may notice.
+#[derive(Debug, Clone, Copy)]
+pub struct PerceptionTarget {
+    pub entity: Entity,
+    pub position: Vec3,
+    /// Lit targets are visible from afar in the dark
+    pub is_lit: bool,
+}
+
+/// Sound of a fight somebody made where creatures can hear it.
+#[derive(Debug, Clone, Copy)]
+pub struct Noise {
+    pub position: Vec3,
+    pub source: Entity,
+    pub faction: Faction,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TargetMemory {
+    pub position: Vec3,
+    pub perceived_at: Instant,
+    pub stimulus: Stimulus,
+}
+
+/// What the creature actually knows about its targets.
+#[derive(Component, Debug, Default)]
+pub struct Perception {
+    config: PerceptionConfig,
+    facing: Option<Vec3>,
+    visible_target: Option<Vec3>,
+    memory: Option<TargetMemory>,
+}
+
+impl Perception {
+    pub fn new(config: PerceptionConfig) -> Self {
+        Perception {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Position of the nearest target the creature sees right now.
+    pub fn visible_target(&self) -> Option<Vec3> {
+        self.visible_target
+    }
+
+    /// Where a target was seen or heard the last time, until the creature forgets it.
+    pub fn last_known_target(&self) -> Option<Vec3> {
+        self.memory().map(|memory| memory.position)
+    }
+
+    pub fn memory(&self) -> Option<&TargetMemory> {
+        self.memory
+            .as_ref()
+            .filter(|memory| memory.perceived_at.elapsed() < self.config.memory)
+    }
+
+    pub fn look(
+        &mut self,
+        physics_world: &PhysicsWorld,
+        phys: &PhysiologyDescription,
+        transform: &Transform,
+        velocity: Vec3,
+        targets: &[PerceptionTarget],
+    ) {
+        let eyes = phys.get_eyes_position(transform);
+        let horizontal_velocity = vec3(velocity.x, 0., velocity.z);
+        if horizontal_velocity.length() > 0.1 {
+            self.facing = Some(horizontal_velocity.normalize());
+        } else if let Some(memory) = self.memory() {
+            // Stands and looks where the target was
+            let to_target = memory.position - eyes;
+            self.facing = vec3(to_target.x, 0., to_target.z).try_normalize();
+        }
+
+        let mut noticeable: Vec<&PerceptionTarget> = targets
+            .iter()
+            .filter(|target| self.is_noticeable(eyes, target.position, target.is_lit))
+            .collect();
+        noticeable.sort_by(|a, b| {
+            let a = a.position.distance_squared(eyes);
+            let b = b.position.distance_squared(eyes);
+            a.partial_cmp(&b).unwrap()
+        });
+        self.visible_target = noticeable
+            .into_iter()
+            .find(|target| phys.can_see(physics_world, transform, target.position, target.entity))
+            .map(|target| target.position);
+
+        if let Some(position) = self.visible_target {
+            self.remember(position, Stimulus::Sight);
+        }
+    }
+
+    /// Only hostiles are worth going after, the own noises and the ones of allies are ignored.
+    pub fn hear(&mut self, listener: Entity, faction: Faction, position: Vec3, noise: &Noise) {
+        if self.visible_target.is_none()
+            && noise.source != listener
+            && faction.is_hostile_to(noise.faction)
+            && position.distance(noise.position) <= self.config.hearing_range
+        {
+            self.remember(noise.position, Stimulus::Hearing);
+        }
+    }
+
+    fn remember(&mut self, position: Vec3, stimulus: Stimulus) {
+        self.memory = Some(TargetMemory {
+            position,
+            perceived_at: Instant::now(),
+            stimulus,
+        });
+    }
+
+    fn is_noticeable(&self, eyes: Vec3, target: Vec3, is_lit: bool) -> bool {
+        let to_target = target - eyes;
+        let distance = to_target.length();
+        if distance <= CLOSE_RANGE {
+            return true;
+        }
+
+        let range = if is_lit {
+            self.config.sight_range
+        } else {
+            self.config.dark_sight_range
+        };
+        let direction = vec3(to_target.x, 0., to_target.z).try_normalize();
+        let in_cone = match (self.facing, direction) {
+            (Some(facing), Some(direction)) => {
+                facing.angle_between(direction) <= self.config.field_of_view / 2.
+            }
+            _ => true,
+        };
+
+        distance <= range && in_cone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facing_x() -> Perception {
+        Perception {
+            facing: Some(Vec3::X),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sees_only_inside_the_cone() {
+        let perception = facing_x();
+        let eyes = Vec3::ZERO;
+
+        assert!(perception.is_noticeable(eyes, vec3(5., 0., 1.), true));
+        assert!(!perception.is_noticeable(eyes, vec3(-5., 0., 0.), true));
+        assert!(!perception.is_noticeable(eyes, vec3(1., 0., 5.), true));
+        // too close to sneak past
+        assert!(perception.is_noticeable(eyes, vec3(-1.5, 0., 0.), false));
+        // without a facing yet the creature looks everywhere
+        assert!(Perception::default().is_noticeable(eyes, vec3(-5., 0., 0.), true));
+    }
+
+    #[test]
+    fn dark_shortens_the_sight() {
+        let perception = facing_x();
+        let eyes = Vec3::ZERO;
+
+        assert!(perception.is_noticeable(eyes, vec3(10., 0., 0.), true));
+        assert!(!perception.is_noticeable(eyes, vec3(10., 0., 0.), false));
+        assert!(perception.is_noticeable(eyes, vec3(5., 0., 0.), false));
+        assert!(!perception.is_noticeable(eyes, vec3(20., 0., 0.), true));
+    }
+
+    fn noise(x: f32, source: u32, faction: Faction) -> Noise {
+        Noise {
+            position: vec3(x, 0., 0.),
+            source: Entity::from_raw(source),
+            faction,
+        }
+    }
+
+    #[test]
+    fn hears_within_the_range() {
+        let creature = Entity::from_raw(0);
+        let mut perception = Perception::default();
+        perception.hear(
+            creature,
+            Faction::Enemy,
+            Vec3::ZERO,
+            &noise(12., 1, Faction::Player),
+        );
+        assert!(perception.last_known_target().is_none());
+
+        perception.hear(
+            creature,
+            Faction::Enemy,
+            Vec3::ZERO,
+            &noise(8., 1, Faction::Player),
+        );
+        assert_eq!(perception.last_known_target(), Some(vec3(8., 0., 0.)));
+        assert_eq!(perception.memory().unwrap().stimulus, Stimulus::Hearing);
+
+        // the seen target matters more than noises
+        perception.visible_target = Some(vec3(3., 0., 0.));
+        perception.hear(
+            creature,
+            Faction::Enemy,
+            Vec3::ZERO,
+            &noise(-4., 1, Faction::Player),
+        );
+        assert_eq!(perception.last_known_target(), Some(vec3(8., 0., 0.)));
+    }
+
+    #[test]
+    fn ignores_noises_of_allies() {
+        let creature = Entity::from_raw(0);
+        let mut perception = Perception::default();
+        perception.hear(
+            creature,
+            Faction::Enemy,
+            Vec3::ZERO,
+            &noise(4., 1, Faction::Enemy),
+        );
+        perception.hear(
+            creature,
+            Faction::Enemy,
+            Vec3::ZERO,
+            &noise(4., 2, Faction::Neutral),
+        );
+        assert!(perception.last_known_target().is_none());
+
+        perception.hear(
+            creature,
+            Faction::Friend,
+            Vec3::ZERO,
+            &noise(4., 1, Faction::Enemy),
+        );
+        assert_eq!(perception.last_known_target(), Some(vec3(4., 0., 0.)));
+    }
+
+    #[test]
+    fn ignores_own_noises() {
+        let creature = Entity::from_raw(0);
+        let mut perception = Perception::default();
+        // whatever side the noise was made for, the creature doesn't hunt itself
+        perception.hear(
+            creature,
+            Faction::Enemy,
+            Vec3::ZERO,
+            &noise(3., 0, Faction::Friend),
+        );
+        assert!(perception.last_known_target().is_none());
+    }
+}
@@ -1,5 +1,8 @@
 use bevy::math::vec3;
 use bevy::prelude::*;
+use heron::rapier_plugin::PhysicsWorld;
+use heron::CollisionShape;
+use lib::entity::level::navigation::BodySize;
 
 #[derive(Component, Debug)]
 pub struct PhysiologyDescription {
@@ -7,16 +10,15 @@ pub struct PhysiologyDescription {
     pub model_radius: f32,
     pub jump_height: f32,
     pub movement_speed: f32,
+    /// Above the center of the body
     pub eyes_height: f32,
 }
 
 impl Default for PhysiologyDescription {
     fn default() -> Self {
         PhysiologyDescription {
-            // TODO use
             model_height: 2.0,
             model_radius: 0.5,
-            // TODO use
             jump_height: 1.0,
             movement_speed: 5.,
             eyes_height: 1.0,
@@ -25,6 +27,14 @@ impl Default for PhysiologyDescription {
 }
 
 impl PhysiologyDescription {
+    /// Cylinder around the whole body, the position of the entity is its center.
+    pub fn collision_shape(&self) -> CollisionShape {
+        CollisionShape::Cylinder {
+            radius: self.model_radius,
+            half_height: self.model_height / 2.,
+        }
+    }
+
     pub fn get_eyes_position(&self, transform: &Transform) -> Vec3 {
         let radius = self.model_radius + 0.1;
         let (_, y_rotation, _) = transform.rotation.to_euler(EulerRot::XYZ);
@@ -35,4 +45,26 @@ impl PhysiologyDescription {
 
         vec3(start_x, start_y, start_z)
     }
+
+    pub fn can_see(
+        &self,
+        physics_world: &PhysicsWorld,
+        transform: &Transform,
+        target_position: Vec3,
+        target: Entity,
+    ) -> bool {
+        let eyes_pos = self.get_eyes_position(transform);
+
+        physics_world
+            .ray_cast(eyes_pos, target_position - eyes_pos, true)
+            .map_or(true, |cast| cast.entity == target)
+    }
+
+    pub fn body_size(&self) -> BodySize {
+        BodySize {
+            height: self.model_height,
+            radius: self.model_radius,
+            jump_height: self.jump_height,
+        }
+    }
 }
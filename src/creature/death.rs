@@ -0,0 +1,270 @@
+use crate::creature::component::attack::component::AttackSet;
+use crate::creature::component::attack::event::Died;
+use crate::creature::component::attack::system::attack_apply_damage;
+use crate::creature::component::behaviour::Behaviour;
+use crate::creature::component::faction::Faction;
+use crate::creature::component::hp::HP;
+use crate::creature::component::movement::MovementStrategy;
+use crate::creature::component::perception::Perception;
+use crate::creature::{CreatureKind, CreatureMarker};
+use crate::level::LevelObjectMarker;
+use crate::player::PlayerMarker;
+use crate::util::component::ShortLife;
+use crate::{GamePhysicsLayer, GameState};
+use bevy::math::vec3;
+use bevy::prelude::*;
+use heron::{AxisAngle, CollisionLayers, CollisionShape, RigidBody, RotationConstraints, Velocity};
+use lib::entity::archetype::{CreatureArchetypes, LootEntry};
+use lib::entity::level::creature::CreatureName;
+use rand::Rng;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Angular speed the ragdoll starts falling with
+const RAGDOLL_SPIN: f32 = 3.0;
+// Loot drops around the body, not inside it
+const LOOT_SCATTER: f32 = 0.7;
+// The player picks up loot a bit before stepping onto it
+const PICKUP_RADIUS: f32 = 0.5;
+
+pub struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KillStats>()
+            .add_startup_system(death_setup_loot_assets)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    // Kills are counted before the body loses its `CreatureKind`
+                    .with_system(death_count_kills.after(attack_apply_damage))
+                    .with_system(death_kill_creatures.after(death_count_kills))
+                    .with_system(death_play_animations),
+            );
+    }
+}
+
+/// Creatures killed by the player during the session.
+#[derive(Debug, Default)]
+pub struct KillStats {
+    pub kills: HashMap<CreatureName, u32>,
+    pub score: u32,
+}
+
+impl KillStats {
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+}
+
+/// Body of a dead creature, lies until its `ShortLife` ends.
+/// It isn't a creature anymore, so it has no `CreatureKind` and `HP`.
+#[derive(Component, Debug)]
+pub struct Dying {
+    animation: Option<Handle<AnimationClip>>,
+}
+
+/// Item lying on the ground, the player picks it up by touching its sensor.
+#[derive(Component, Debug)]
+pub struct LootDrop {
+    pub item: String,
+    pub count: u16,
+}
+
+struct LootAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+fn death_setup_loot_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(LootAssets {
+        mesh: meshes.add(Mesh::from(shape::Cube { size: 0.3 })),
+        material: materials.add(StandardMaterial {
+            base_color: Color::GOLD,
+            ..Default::default()
+        }),
+    });
+}
+
+fn death_kill_creatures(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    asset_server: Res<AssetServer>,
+    archetypes: Res<CreatureArchetypes>,
+    loot_assets: Res<LootAssets>,
+    positions: Query<&Transform>,
+    mut creatures: Query<(&CreatureKind, &Transform, &mut Velocity), With<CreatureMarker>>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for event in died_events.iter() {
+        let (kind, transform, mut velocity) = if let Ok(creature) = creatures.get_mut(event.entity)
+        {
+            creature
+        } else {
+            continue;
+        };
+        let archetype = archetypes
+            .get(&kind.0)
+            .expect("Spawned creature should have an archetype");
+        let death = &archetype.death;
+
+        let mut body = commands.entity(event.entity);
+        body.remove::<Behaviour>()
+            .remove::<MovementStrategy>()
+            .remove::<Perception>()
+            .remove::<AttackSet>()
+            .remove::<Faction>()
+            .remove::<CreatureKind>()
+            .remove::<HP>()
+            .insert(Dying {
+                animation: death
+                    .animation
+                    .as_ref()
+                    .map(|animation| asset_server.load(animation.as_str())),
+            })
+            .insert(ShortLife::new(Duration::from_secs_f32(
+                death.corpse_time.max(0.),
+            )));
+
+        *velocity = Velocity::from_linear(vec3(0., velocity.linear.y, 0.));
+        if death.ragdoll {
+            // Falls away from the killer
+            let killer = event
+                .killer
+                .and_then(|killer| positions.get(killer).ok())
+                .map(|killer| transform.translation - killer.translation);
+            let away = killer
+                .and_then(|away| vec3(away.x, 0., away.z).try_normalize())
+                .unwrap_or(Vec3::X);
+            body.remove::<RotationConstraints>();
+            *velocity = velocity.with_angular(AxisAngle::new(Vec3::Y.cross(away), RAGDOLL_SPIN));
+        }
+
+        for entry in &archetype.loot {
+            if let Some(count) = roll_loot(&mut rng, entry) {
+                let scatter = vec3(
+                    rng.gen_range(-LOOT_SCATTER..LOOT_SCATTER),
+                    0.,
+                    rng.gen_range(-LOOT_SCATTER..LOOT_SCATTER),
+                );
+                commands
+                    .spawn_bundle(PbrBundle {
+                        mesh: loot_assets.mesh.clone(),
+                        material: loot_assets.material.clone(),
+                        transform: Transform::from_translation(transform.translation + scatter),
+                        ..Default::default()
+                    })
+                    .insert(LootDrop {
+                        item: entry.item.clone(),
+                        count,
+                    })
+                    .insert(RigidBody::Sensor)
+                    .insert(CollisionShape::Sphere {
+                        radius: PICKUP_RADIUS,
+                    })
+                    .insert(
+                        CollisionLayers::all_masks::<GamePhysicsLayer>()
+                            .with_group(GamePhysicsLayer::Sensor),
+                    )
+                    .insert(LevelObjectMarker);
+            }
+        }
+    }
+}
+
+fn roll_loot(rng: &mut impl Rng, entry: &LootEntry) -> Option<u16> {
+    if !rng.gen_bool(f64::from(entry.chance.clamp(0., 1.))) {
+        return None;
+    }
+    let count = rng.gen_range(entry.min..=entry.max.max(entry.min));
+    (count > 0).then_some(count)
+}
+
+fn death_count_kills(
+    mut died_events: EventReader<Died>,
+    mut stats: ResMut<KillStats>,
+    archetypes: Res<CreatureArchetypes>,
+    players: Query<Entity, With<PlayerMarker>>,
+    creatures: Query<&CreatureKind>,
+) {
+    for event in died_events.iter() {
+        let by_player = event
+            .killer
+            .map_or(false, |killer| players.get(killer).is_ok());
+        if !by_player {
+            continue;
+        }
+
+        if let Ok(kind) = creatures.get(event.entity) {
+            *stats.kills.entry(kind.0.clone()).or_insert(0) += 1;
+            stats.score += archetypes
+                .get(&kind.0)
+                .map_or(0, |archetype| archetype.score);
+        }
+    }
+}
+
+/// Animation players live somewhere inside the scene of the body.
+fn death_play_animations(
+    mut players: Query<(Entity, &mut AnimationPlayer)>,
+    parents: Query<&Parent>,
+    mut bodies: Query<&mut Dying>,
+) {
+    if bodies.is_empty() {
+        return;
+    }
+
+    for (entity, mut player) in players.iter_mut() {
+        let mut ancestor = entity;
+        while let Ok(parent) = parents.get(ancestor) {
+            ancestor = parent.get();
+            if let Ok(mut body) = bodies.get_mut(ancestor) {
+                if let Some(animation) = body.animation.take() {
+                    player.play(animation);
+                }
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn entry(chance: f32, min: u16, max: u16) -> LootEntry {
+        LootEntry {
+            item: "pizza_slice".to_string(),
+            chance,
+            min,
+            max,
+        }
+    }
+
+    fn rolls(entry: &LootEntry) -> Vec<Option<u16>> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..100).map(|_| roll_loot(&mut rng, entry)).collect()
+    }
+
+    #[test]
+    fn chance_decides_whether_loot_drops() {
+        assert!(rolls(&entry(0., 1, 1)).iter().all(Option::is_none));
+        assert!(rolls(&entry(1., 2, 4))
+            .iter()
+            .all(|count| matches!(count, Some(2..=4))));
+    }
+
+    #[test]
+    fn counts_are_clamped() {
+        assert!(rolls(&entry(1., 3, 1))
+            .iter()
+            .all(|count| *count == Some(3)));
+        assert!(rolls(&entry(1., 0, 0)).iter().all(Option::is_none));
+    }
+}
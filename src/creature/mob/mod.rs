@@ -1,2 +0,0 @@
-pub mod pizza;
-pub mod dummy;
\ No newline at end of file
@@ -1,30 +1,51 @@
-use crate::creature::component::attack::component::Attack;
+use crate::creature::component::attack::component::{
+    strike, AttackSet, AttackTarget, Attacker, Strike,
+};
 use crate::creature::component::attack::AttackPlugin;
-use crate::creature::component::movement::MovementStrategy;
+use crate::audio::SoundEvent;
+use crate::creature::component::attack::event::DamageEvent;
+use crate::creature::component::behaviour::Behaviour;
+use crate::creature::component::faction::Faction;
+use crate::creature::component::movement::steering::{self, Neighbour, SpatialIndex};
+use crate::creature::component::movement::{locomotivity, MovementStrategy};
+use crate::creature::component::perception::{Noise, Perception, PerceptionTarget};
 use crate::creature::component::physiology_description::PhysiologyDescription;
 
+use crate::creature::activity::{Activity, ActivityPlugin};
+use crate::creature::boss::BossPlugin;
 use crate::creature::buffs::BuffsPlugin;
-use crate::creature::component::hp::{creature_hp_mesh_change_percent, creature_hp_spawn_mesh};
-use crate::creature::mob::{dummy, pizza};
-use crate::player::PlayerMarker;
+use crate::creature::death::DeathPlugin;
+use crate::creature::spawner::SpawnerPlugin;
+use crate::creature::component::hp::{creature_hp_mesh_change_percent, creature_hp_spawn_mesh, HP};
+use crate::level::LevelObjectMarker;
 use crate::{GamePhysicsLayer, GameState};
-use bevy::math::vec3;
 use bevy::prelude::*;
 use heron::prelude::*;
 use heron::rapier_plugin::PhysicsWorld;
-use heron::{CollisionLayers, CollisionShape};
-use lib::entity::level::creature::CreatureName;
+use heron::CollisionLayers;
+use lib::entity::archetype::CreatureArchetypes;
+use lib::entity::level::creature::{Creature, CreatureName};
+use lib::entity::level::navigation::Pathfinder;
 use lib::entity::level::Level;
-use std::f32::consts::PI;
 
+const CREATURE_DIR: &str = "assets/creature";
+// Creatures appear a bit above the floor so they don't get stuck in it
+const SPAWN_LIFT: f32 = 0.1;
+// The player is visible from afar if stands closer to a light than this part of its range
+const LIT_RANGE_PART: f32 = 0.5;
+
+pub mod activity;
+pub mod archetype;
+pub mod boss;
 pub mod buffs;
 pub mod component;
-pub mod mob;
+pub mod death;
+pub mod spawner;
 
 #[derive(Component, Debug)]
 pub struct CreatureMarker;
 #[derive(Component, Debug)]
-pub struct EnemyCreatureMarker;
+pub struct CreatureKind(pub CreatureName);
 
 #[allow(clippy::module_name_repetitions)]
 pub struct CreaturePlugin;
@@ -32,11 +53,23 @@ pub struct CreaturePlugin;
 impl Plugin for CreaturePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(AttackPlugin)
-            .add_startup_system_to_stage(StartupStage::PostStartup, spawn_creatures)
+            .add_plugin(ActivityPlugin)
+            .add_plugin(BossPlugin)
+            .add_plugin(DeathPlugin)
+            .add_plugin(SpawnerPlugin)
+            .insert_resource(CreatureArchetypes::from_dir(CREATURE_DIR))
+            .init_resource::<Pathfinder>()
+            .init_resource::<SpatialIndex>()
+            .add_system(creature_clear_pathfinder_cache)
+            .add_system(spawn_creatures)
             .add_system_set(
                 SystemSet::on_update(GameState::Playing)
-                    .with_system(creature_execute_move_strategies)
-                    .with_system(creature_attack_player)
+                    .with_system(creature_perceive)
+                    // Behaviour and movement act on what the creature perceived in this frame
+                    .with_system(creature_update_behaviour.after(creature_perceive))
+                    .with_system(creature_execute_move_strategies.after(creature_update_behaviour))
+                    .with_system(creature_provoke_neutrals)
+                    .with_system(creature_attack_targets)
                     .with_system(creature_hp_mesh_change_percent)
                     .with_system(creature_hp_spawn_mesh),
             )
@@ -44,53 +77,220 @@ impl Plugin for CreaturePlugin {
     }
 }
 
-fn spawn_creatures(mut commands: Commands, level: Res<Level>, asset_server: Res<AssetServer>) {
+fn spawn_creatures(
+    mut commands: Commands,
+    level: Res<Level>,
+    asset_server: Res<AssetServer>,
+    archetypes: Res<CreatureArchetypes>,
+) {
+    if !level.is_changed() {
+        return;
+    }
+
     for creature in level.creatures() {
-        let mut ec = commands.spawn_bundle(SceneBundle {
-            scene: match creature.name {
-                CreatureName::Dummy => asset_server.load("mesh/dummy.glb#Scene0"),
-                CreatureName::Pizza => asset_server.load("mesh/pizza.glb#Scene0"),
-            },
-            transform: Transform::from_xyz(
-                creature.position.x,
-                creature.position.y + 0.5, // To prevent stucking in the ground
-                creature.position.z,
-            )
-            //     TODO remove default rotation after debug
-            .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.0, PI / 2.0, 0.0))
-            //     TODO make sth to avoid this
-            .with_scale(vec3(0.5, 0.5, 0.5)),
-            ..Default::default()
-        });
-        ec.insert(CreatureMarker)
-            .insert(RigidBody::Dynamic)
-            .insert(RotationConstraints::lock())
-            .insert(CollisionShape::Cylinder {
-                radius: 0.5,
-                half_height: 1.0,
+        spawn_creature(&mut commands, &asset_server, &archetypes, creature);
+    }
+}
+
+pub fn spawn_creature(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    archetypes: &CreatureArchetypes,
+    creature: &Creature,
+) -> Option<Entity> {
+    let archetype = if let Some(archetype) = archetypes.get(&creature.name) {
+        archetype
+    } else {
+        error!("Creature {} has no archetype", creature.name);
+        return None;
+    };
+
+    let height = archetype.physiology.model_height;
+    let mut ec = commands.spawn_bundle(TransformBundle::from_transform(
+        Transform::from_xyz(
+            creature.position.x,
+            creature.position.y + height / 2. + SPAWN_LIFT,
+            creature.position.z,
+        )
+        .with_rotation(Quat::from_rotation_y(archetype.yaw.to_radians())),
+    ));
+    ec.insert_bundle(VisibilityBundle::default())
+        .with_children(|parent| {
+            // The mesh stands on the floor while the body is centered, scaling it doesn't touch physics
+            parent.spawn_bundle(SceneBundle {
+                scene: asset_server.load(archetype.mesh.as_str()),
+                transform: Transform::from_xyz(0., -height / 2., 0.)
+                    .with_scale(Vec3::splat(archetype.scale)),
+                ..Default::default()
+            });
+        })
+        .insert(CreatureMarker)
+        .insert(CreatureKind(creature.name.clone()))
+        .insert(LevelObjectMarker)
+        .insert(RigidBody::Dynamic)
+        .insert(RotationConstraints::lock())
+        .insert(
+            CollisionLayers::all_masks::<GamePhysicsLayer>().with_group(GamePhysicsLayer::Creature),
+        )
+        .insert(Faction::from(creature.relation()));
+
+    let patrol = creature
+        .patrol
+        .as_ref()
+        .filter(|route| !route.waypoints.is_empty())
+        .map(|route| MovementStrategy::patrol(route.clone()));
+    archetype::insert(&mut ec, archetype, patrol);
+
+    Some(ec.id())
+}
+
+/// Spawns a creature of the archetype with its default relation.
+pub fn spawn_archetype(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    archetypes: &CreatureArchetypes,
+    name: &CreatureName,
+    position: Vec3,
+) -> Option<Entity> {
+    let relation = if let Some(archetype) = archetypes.get(name) {
+        archetype.relation
+    } else {
+        error!("Creature {name} has no archetype");
+        return None;
+    };
+
+    let creature = Creature::new(name.clone(), relation, position);
+    spawn_creature(commands, asset_server, archetypes, &creature)
+}
+
+fn creature_clear_pathfinder_cache(level: Res<Level>, pathfinder: Res<Pathfinder>) {
+    if level.is_changed() {
+        pathfinder.clear();
+    }
+}
+
+// Projectiles and melee sensors carry a `Faction` too, only living ones are perceived
+type TargetQuery<'w, 's> = Query<'w, 's, (Entity, &'static Transform, &'static Faction), With<HP>>;
+
+fn creature_perceive(
+    level: Res<Level>,
+    physics_world: PhysicsWorld,
+    mut sound_events: EventReader<SoundEvent>,
+    targets: TargetQuery,
+    factions: Query<&Faction>,
+    lights: Query<(&PointLight, &GlobalTransform)>,
+    mut query: Query<(
+        Entity,
+        &PhysiologyDescription,
+        &Transform,
+        &Velocity,
+        &Faction,
+        &Activity,
+        &mut Perception,
+    )>,
+) {
+    let targets = perception_targets(&level, &targets, &lights);
+    let noises: Vec<Noise> = sound_events
+        .iter()
+        .filter_map(|event| {
+            let source = event.source?;
+            Some(Noise {
+                position: event.position?,
+                source,
+                faction: *factions.get(source).ok()?,
             })
-            .insert(
-                CollisionLayers::all_masks::<GamePhysicsLayer>()
-                    .with_group(GamePhysicsLayer::Creature),
-            )
-            .insert(EnemyCreatureMarker);
+        })
+        .collect();
 
-        match creature.name {
-            CreatureName::Dummy => {
-                dummy::insert(&mut ec);
-            }
-            CreatureName::Pizza => {
-                pizza::insert(&mut ec);
+    for (entity, phys, transform, velocity, faction, activity, mut perception) in query.iter_mut() {
+        let hostile: Vec<PerceptionTarget> = targets
+            .iter()
+            .filter(|(target, target_faction)| {
+                target.entity != entity && faction.is_hostile_to(*target_faction)
+            })
+            .map(|(target, _)| *target)
+            .collect();
+
+        // Sleeping creatures still hear, it's what wakes them up.
+        // Looking with no hostiles left forgets the target which has just died
+        if activity.is_ticking() {
+            perception.look(&physics_world, phys, transform, velocity.linear, &hostile);
+        }
+        for noise in &noises {
+            perception.hear(entity, *faction, transform.translation, noise);
+        }
+    }
+}
+
+fn perception_targets(
+    level: &Level,
+    targets: &TargetQuery,
+    lights: &Query<(&PointLight, &GlobalTransform)>,
+) -> Vec<(PerceptionTarget, Faction)> {
+    targets
+        .iter()
+        .map(|(entity, transform, faction)| {
+            let mut position = transform.translation;
+            position.y += 0.5;
+            let is_lit = level.is_day()
+                || lights.iter().any(|(light, transform)| {
+                    transform.translation().distance(position) <= light.range * LIT_RANGE_PART
+                });
+
+            let target = PerceptionTarget {
+                entity,
+                position,
+                is_lit,
+            };
+            (target, *faction)
+        })
+        .collect()
+}
+
+fn creature_provoke_neutrals(
+    mut damage_events: EventReader<DamageEvent>,
+    mut factions: Query<&mut Faction>,
+) {
+    for event in damage_events.iter() {
+        let source = event
+            .source
+            .and_then(|source| factions.get(source).ok().copied());
+        if let Ok(mut faction) = factions.get_mut(event.target) {
+            if faction.is_provoked_by(source) {
+                *faction = Faction::Enemy;
             }
         }
     }
 }
 
+fn creature_update_behaviour(
+    mut query: Query<(
+        &Perception,
+        &Transform,
+        &HP,
+        &Activity,
+        &mut Behaviour,
+        &mut MovementStrategy,
+    )>,
+) {
+    for (perception, transform, hp, activity, mut behaviour, mut movement) in query.iter_mut() {
+        if !activity.is_ticking() {
+            continue;
+        }
+        behaviour.update(perception, transform.translation, hp.percent(), &mut movement);
+    }
+}
+
 fn creature_execute_move_strategies(
     lvl: Res<Level>,
+    pathfinder: Res<Pathfinder>,
+    mut index: ResMut<SpatialIndex>,
     mut query: Query<
         (
+            Entity,
             &PhysiologyDescription,
+            &Perception,
+            &Activity,
             &mut MovementStrategy,
             &Transform,
             &mut Velocity,
@@ -98,35 +298,133 @@ fn creature_execute_move_strategies(
         With<CreatureMarker>,
     >,
 ) {
-    for (phys, mut move_strat, transform, mut velocity) in query.iter_mut() {
-        move_strat.update(phys, &lvl, transform, &mut velocity);
+    index.clear();
+    for (entity, phys, _, _, _, transform, velocity) in query.iter() {
+        index.insert(Neighbour {
+            entity,
+            position: transform.translation,
+            velocity: velocity.linear,
+            radius: phys.model_radius,
+        });
+    }
+
+    for (entity, phys, perception, activity, mut move_strat, transform, mut velocity) in
+        query.iter_mut()
+    {
+        if !activity.is_ticking() {
+            continue;
+        }
+
+        move_strat.update(
+            phys,
+            &lvl,
+            &pathfinder,
+            perception,
+            transform,
+            &mut velocity,
+        );
+
+        let position = transform.translation;
+        let body = Neighbour {
+            entity,
+            position,
+            velocity: velocity.linear,
+            radius: phys.model_radius,
+        };
+        let steered = steering::steer(&body, velocity.linear, phys.movement_speed, &index);
+        velocity.linear = steering::avoid_obstacles(&lvl, phys, position, steered, velocity.linear);
+        locomotivity::step_up(&lvl, phys, position, &mut velocity);
     }
 }
 
-fn creature_attack_player(
-    player_query: Query<(Entity, &Transform), With<PlayerMarker>>,
-    physics_world: PhysicsWorld,
+fn creature_attack_targets(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut enemy_query: Query<
-        (&Transform, &PhysiologyDescription, &mut Attack),
-        With<EnemyCreatureMarker>,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &PhysiologyDescription,
+            &Perception,
+            &Faction,
+            &Activity,
+            &mut AttackSet,
+            Option<&Behaviour>,
+        ),
+        With<CreatureMarker>,
     >,
 ) {
-    if let Some((id, player_transform)) = player_query.iter().next() {
-        let mut player_position = player_transform.translation;
-        player_position.y += 0.5;
-
-        for (transform, phys, mut attack) in enemy_query.iter_mut() {
-            attack.exec(
-                &physics_world,
-                phys,
-                transform,
-                player_position,
-                id,
+    for (entity, transform, phys, perception, faction, activity, mut attacks, behaviour) in
+        query.iter_mut()
+    {
+        if !activity.is_ticking() {
+            continue;
+        }
+
+        let position = transform.translation;
+        let target = perception
+            .visible_target()
+            .map(|target| (target, true))
+            .or_else(|| perception.last_known_target().map(|target| (target, false)))
+            .filter(|_| behaviour.map_or(true, Behaviour::can_attack))
+            .map(|(target, is_visible)| AttackTarget {
+                position: target,
+                distance: (target.distance(position) - phys.model_radius).max(0.),
+                is_visible,
+            });
+
+        if let Some(Strike { attack, aim }) = attacks.update(target) {
+            let attacker = Attacker {
+                entity,
+                faction: *faction,
+                position,
+                eyes: phys.get_eyes_position(transform),
+                model_radius: phys.model_radius,
+            };
+            strike(
                 &mut commands,
                 &mut meshes,
+                &attacker,
+                attacks.attack(attack),
+                aim,
             );
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::SystemState;
+    use lib::entity::level::DayPart;
+
+    #[test]
+    fn only_living_combatants_are_targets() {
+        let mut world = World::new();
+        world.insert_resource(Level::new("test".to_string(), vec![], DayPart::Day, vec![]));
+        let player = world
+            .spawn()
+            .insert(Transform::default())
+            .insert(Faction::Player)
+            .insert(HP::full(100))
+            .id();
+        // a projectile of the player
+        world
+            .spawn()
+            .insert(Transform::default())
+            .insert(Faction::Player);
+
+        let mut state: SystemState<(
+            Res<Level>,
+            TargetQuery,
+            Query<(&PointLight, &GlobalTransform)>,
+        )> = SystemState::new(&mut world);
+        let (level, targets, lights) = state.get(&world);
+        let found: Vec<Entity> = perception_targets(&level, &targets, &lights)
+            .iter()
+            .map(|(target, _)| target.entity)
+            .collect();
+
+        assert_eq!(found, vec![player]);
+    }
+}
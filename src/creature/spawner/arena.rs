@@ -0,0 +1,187 @@
+use crate::creature::spawn_archetype;
+use crate::creature::spawner::AliveCreatures;
+use crate::player::PlayerMarker;
+use bevy::prelude::*;
+use lib::entity::archetype::CreatureArchetypes;
+use lib::entity::level::spawn::ArenaParams;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaState {
+    /// Waits for the player to come
+    Waiting,
+    Fighting,
+    /// The wave is cleared, the next one comes after the break
+    Break(Instant),
+    Finished,
+}
+
+/// Waves of creatures, every next one is bigger.
+#[derive(Debug)]
+pub struct Arena {
+    params: ArenaParams,
+    state: ArenaState,
+    wave: u32,
+    alive: Vec<Entity>,
+}
+
+impl Arena {
+    pub fn new(params: ArenaParams) -> Self {
+        Arena {
+            params,
+            state: ArenaState::Waiting,
+            wave: 0,
+            alive: vec![],
+        }
+    }
+
+    pub fn state(&self) -> ArenaState {
+        self.state
+    }
+
+    /// The current wave counting from 1, 0 before the first one.
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    pub fn alive(&self) -> usize {
+        self.alive.len()
+    }
+
+    /// Moves on by the time and the player, true when the next wave has to be spawned.
+    fn update(&mut self, now: Instant, player_position: Option<Vec3>) -> bool {
+        let next_wave = match self.state {
+            ArenaState::Waiting => player_position.map_or(false, |position| {
+                position.distance(self.params.center) <= self.params.activation_radius
+            }),
+            ArenaState::Fighting => {
+                if self.alive.is_empty() {
+                    self.state = if self.params.is_last_wave(self.wave) {
+                        ArenaState::Finished
+                    } else {
+                        ArenaState::Break(now)
+                    };
+                }
+                false
+            }
+            ArenaState::Break(since) => {
+                now >= since + Duration::from_secs_f32(self.params.break_time.max(0.))
+            }
+            ArenaState::Finished => false,
+        };
+
+        if next_wave {
+            self.wave += 1;
+            self.state = ArenaState::Fighting;
+        }
+        next_wave
+    }
+
+    fn spawn_point(&self, index: usize) -> Vec3 {
+        let points = &self.params.spawn_points;
+        if points.is_empty() {
+            self.params.center
+        } else {
+            points[index % points.len()]
+        }
+    }
+}
+
+pub fn spawner_run_arena(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    archetypes: Res<CreatureArchetypes>,
+    arena: Option<ResMut<Arena>>,
+    player: Query<&Transform, With<PlayerMarker>>,
+    alive: AliveCreatures,
+) {
+    let mut arena = if let Some(arena) = arena {
+        arena
+    } else {
+        return;
+    };
+    arena.alive.retain(|creature| alive.get(*creature).is_ok());
+
+    let player_position = player.get_single().ok().map(|player| player.translation);
+    if !arena.update(Instant::now(), player_position) {
+        return;
+    }
+
+    let creatures = arena.params.wave_creatures(arena.wave);
+    for (i, name) in creatures.iter().enumerate() {
+        let position = arena.spawn_point(i);
+        if let Some(creature) =
+            spawn_archetype(&mut commands, &asset_server, &archetypes, name, position)
+        {
+            arena.alive.push(creature);
+        }
+    }
+    info!(
+        "Arena wave {} with {} creatures",
+        arena.wave,
+        arena.alive.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::vec3;
+    use lib::entity::level::creature::CreatureName;
+    use lib::entity::level::spawn::ArenaCreature;
+
+    fn arena(waves: u32) -> Arena {
+        Arena::new(ArenaParams {
+            center: Vec3::ZERO,
+            activation_radius: 5.0,
+            spawn_points: vec![],
+            creatures: vec![ArenaCreature {
+                name: CreatureName::from("Pizza"),
+                from_wave: 0,
+            }],
+            first_wave_size: 2,
+            wave_growth: 1,
+            break_time: 5.0,
+            waves,
+        })
+    }
+
+    #[test]
+    fn waves_come_after_breaks_until_the_last() {
+        let start = Instant::now();
+        let mut arena = arena(2);
+        assert!(!arena.update(start, None));
+        assert!(!arena.update(start, Some(vec3(8., 0., 0.))));
+        assert!(arena.update(start, Some(vec3(3., 0., 0.))));
+        assert_eq!(arena.wave(), 1);
+        assert_eq!(arena.state(), ArenaState::Fighting);
+
+        arena.alive.push(Entity::from_raw(1));
+        assert!(!arena.update(start, None));
+        assert_eq!(arena.state(), ArenaState::Fighting);
+
+        arena.alive.clear();
+        assert!(!arena.update(start, None));
+        assert_eq!(arena.state(), ArenaState::Break(start));
+        assert!(!arena.update(start + Duration::from_secs(4), None));
+        assert!(arena.update(start + Duration::from_secs(5), None));
+        assert_eq!(arena.wave(), 2);
+
+        assert!(!arena.update(start + Duration::from_secs(5), None));
+        assert_eq!(arena.state(), ArenaState::Finished);
+        assert!(!arena.update(start + Duration::from_secs(60), Some(Vec3::ZERO)));
+    }
+
+    #[test]
+    fn endless_arena_never_finishes() {
+        let start = Instant::now();
+        let mut arena = arena(0);
+        arena.update(start, Some(Vec3::ZERO));
+        for wave in 1..10 {
+            assert_eq!(arena.wave(), wave);
+            assert!(!arena.update(start, None));
+            assert_eq!(arena.state(), ArenaState::Break(start));
+            assert!(arena.update(start + Duration::from_secs(5), None));
+        }
+    }
+}
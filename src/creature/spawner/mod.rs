@@ -0,0 +1,167 @@
+use crate::creature::death::Dying;
+use crate::creature::spawner::arena::{spawner_run_arena, Arena};
+use crate::creature::{spawn_archetype, CreatureMarker};
+use crate::level::reader::read_level_spawns;
+use crate::level::LevelObjectMarker;
+use crate::player::PlayerMarker;
+use crate::GameState;
+use bevy::prelude::*;
+use lib::entity::archetype::CreatureArchetypes;
+use lib::entity::level::spawn::SpawnerParams;
+use lib::entity::level::Level;
+use std::time::{Duration, Instant};
+
+pub mod arena;
+
+pub struct SpawnerPlugin;
+
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(spawner_load).add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(spawner_spawn_creatures)
+                .with_system(spawner_run_arena),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Spawner {
+    params: SpawnerParams,
+    spawned: u32,
+    last_spawn: Option<Instant>,
+    alive: Vec<Entity>,
+}
+
+impl Spawner {
+    fn new(params: SpawnerParams) -> Self {
+        Spawner {
+            params,
+            spawned: 0,
+            last_spawn: None,
+            alive: vec![],
+        }
+    }
+
+    fn is_ready(&self, now: Instant, player_position: Vec3) -> bool {
+        let params = &self.params;
+        let exhausted = params.count != 0 && self.spawned >= params.count;
+        let cooled_down = self.last_spawn.map_or(true, |last| {
+            now >= last + Duration::from_secs_f32(params.interval.max(0.))
+        });
+
+        !exhausted
+            && cooled_down
+            && self.alive.len() < params.max_alive as usize
+            && player_position.distance(params.position) <= params.activation_radius
+    }
+}
+
+/// Creatures which are not killed yet, corpses don't count.
+pub type AliveCreatures<'w, 's> = Query<'w, 's, (), (With<CreatureMarker>, Without<Dying>)>;
+
+fn spawner_load(mut commands: Commands, level: Res<Level>) {
+    if !level.is_changed() {
+        return;
+    }
+    commands.remove_resource::<Arena>();
+
+    let spawns = read_level_spawns(&level.name);
+    for params in spawns.spawners {
+        commands
+            .spawn_bundle(TransformBundle::from_transform(
+                Transform::from_translation(params.position),
+            ))
+            .insert(Spawner::new(params))
+            .insert(LevelObjectMarker);
+    }
+    if let Some(arena) = spawns.arena {
+        commands.insert_resource(Arena::new(arena));
+    }
+}
+
+fn spawner_spawn_creatures(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    archetypes: Res<CreatureArchetypes>,
+    player: Query<&Transform, With<PlayerMarker>>,
+    alive: AliveCreatures,
+    mut spawners: Query<&mut Spawner>,
+) {
+    let player_position = if let Ok(transform) = player.get_single() {
+        transform.translation
+    } else {
+        return;
+    };
+
+    let now = Instant::now();
+    for mut spawner in spawners.iter_mut() {
+        spawner
+            .alive
+            .retain(|creature| alive.get(*creature).is_ok());
+        if !spawner.is_ready(now, player_position) {
+            continue;
+        }
+
+        // Counts failed spawns too, so a broken spawner doesn't retry every frame
+        spawner.spawned += 1;
+        spawner.last_spawn = Some(now);
+        let params = &spawner.params;
+        if let Some(creature) = spawn_archetype(
+            &mut commands,
+            &asset_server,
+            &archetypes,
+            &params.creature,
+            params.position,
+        ) {
+            spawner.alive.push(creature);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::vec3;
+    use lib::entity::level::creature::CreatureName;
+
+    fn spawner(count: u32) -> Spawner {
+        Spawner::new(SpawnerParams {
+            creature: CreatureName::from("Pizza"),
+            position: Vec3::ZERO,
+            count,
+            interval: 2.0,
+            max_alive: 2,
+            activation_radius: 10.0,
+        })
+    }
+
+    #[test]
+    fn spawns_near_the_player_until_used_up() {
+        let now = Instant::now();
+        let mut limited = spawner(3);
+        assert!(limited.is_ready(now, vec3(8., 0., 0.)));
+        assert!(!limited.is_ready(now, vec3(12., 0., 0.)));
+
+        limited.spawned = 3;
+        assert!(!limited.is_ready(now, Vec3::ZERO));
+
+        let mut endless = spawner(0);
+        endless.spawned = 100;
+        assert!(endless.is_ready(now, Vec3::ZERO));
+    }
+
+    #[test]
+    fn waits_for_the_interval_and_a_free_place() {
+        let now = Instant::now();
+        let mut spawner = spawner(0);
+        spawner.last_spawn = Some(now);
+        assert!(!spawner.is_ready(now + Duration::from_secs(1), Vec3::ZERO));
+        assert!(spawner.is_ready(now + Duration::from_secs(2), Vec3::ZERO));
+
+        spawner.alive = vec![Entity::from_raw(1), Entity::from_raw(2)];
+        assert!(!spawner.is_ready(now + Duration::from_secs(2), Vec3::ZERO));
+        spawner.alive.pop();
+        assert!(spawner.is_ready(now + Duration::from_secs(2), Vec3::ZERO));
+    }
+}
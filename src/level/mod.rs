@@ -1,11 +1,12 @@
 use crate::{is_sensor, GamePhysicsLayer};
 use bevy::prelude::*;
-use heron::{CollisionEvent, CollisionLayers, CollisionShape, RigidBody};
+use heron::{CollisionEvent, CollisionLayers, CollisionShape, RigidBody, Velocity};
 use lib::entity::level::Level;
 
 use crate::level::reader::read_level;
+use crate::player::{PlayerMarker, SPAWN_POINT};
 
-mod reader;
+pub mod reader;
 mod render;
 
 #[allow(clippy::module_name_repetitions)]
@@ -14,9 +15,11 @@ pub struct LevelPlugin;
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(self.get_level_resource())
-            .add_startup_system(render::level_init)
+            .add_event::<SwitchLevelEvent>()
+            .add_system(render::level_init)
             .add_startup_system(level_spawn_killing_sensor)
-            .add_system(level_kill_entities_on_sensor_touch);
+            .add_system(level_kill_entities_on_sensor_touch)
+            .add_system(level_switch);
     }
 }
 
@@ -27,6 +30,14 @@ impl LevelPlugin {
     }
 }
 
+/// Everything that belongs to the current level and has to be despawned on level switch.
+#[derive(Component)]
+pub struct LevelObjectMarker;
+
+pub struct SwitchLevelEvent {
+    pub name: String,
+}
+
 #[derive(Component)]
 struct KillingSensor;
 
@@ -73,3 +84,22 @@ fn level_kill_entities_on_sensor_touch(
             commands.entity(entity).despawn_recursive();
         });
 }
+
+fn level_switch(
+    mut commands: Commands,
+    mut switch_events: EventReader<SwitchLevelEvent>,
+    level_objects: Query<Entity, With<LevelObjectMarker>>,
+    mut player: Query<(&mut Transform, &mut Velocity), With<PlayerMarker>>,
+) {
+    if let Some(event) = switch_events.iter().last() {
+        for entity in level_objects.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands.insert_resource(read_level(&event.name));
+        // The old position may be inside the terrain of the new level
+        for (mut transform, mut velocity) in player.iter_mut() {
+            transform.translation = SPAWN_POINT;
+            *velocity = Velocity::default();
+        }
+    }
+}
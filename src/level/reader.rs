@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::Read;
 
 use flate2::read::ZlibDecoder;
+use lib::entity::level::spawn::LevelSpawns;
 use lib::entity::level::Level;
 use mca_cuboids::BlockSequence;
 
@@ -21,6 +22,31 @@ pub fn read_level(lvl_name: &str) -> Level {
     serde_json::from_str(&json).expect("Failed to parse lvl")
 }
 
+pub fn read_level_script(lvl_name: &str) -> Option<String> {
+    let path = [LVL_DIR, lvl_name, "/logic.rhai"].concat();
+    let mut source = String::new();
+    File::open(path)
+        .ok()?
+        .read_to_string(&mut source)
+        .expect("Cannot read level script from file");
+
+    Some(source)
+}
+
+/// Levels without the spawns file have no spawners.
+pub fn read_level_spawns(lvl_name: &str) -> LevelSpawns {
+    let path = [LVL_DIR, lvl_name, "/spawns.json"].concat();
+    let mut json = String::new();
+    if let Ok(mut file) = File::open(path) {
+        file.read_to_string(&mut json)
+            .expect("Cannot read level spawns from file");
+    } else {
+        return LevelSpawns::default();
+    }
+
+    serde_json::from_str(&json).expect(&format!("Failed to parse spawns of {lvl_name}"))
+}
+
 pub fn read_level_collisions(lvl_name: &str) -> Vec<BlockSequence> {
     let path = [LVL_DIR, lvl_name, "/collisions.json"].concat();
     let mut json = String::new();
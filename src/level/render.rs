@@ -5,6 +5,7 @@ use lib::entity::level::Level;
 use lib::entity::voxel::Voxel;
 use lib::util::debug_settings::DebugSettings;
 
+use crate::level::LevelObjectMarker;
 use crate::system::light::{spawn_blue_light_source_inside, spawn_orange_light_source_inside};
 use crate::Material;
 
@@ -15,11 +16,15 @@ pub fn level_init(
     level: Res<Level>,
     debug_settings: Res<DebugSettings>,
 ) {
+    if !level.is_changed() {
+        return;
+    }
+
     let collisions = read_level_collisions(&level.name);
     let lvl_width = level.width() as f32;
 
     let mut builder = commands.spawn_bundle(VisibilityBundle::default());
-    builder.insert(RigidBody::Static);
+    builder.insert(RigidBody::Static).insert(LevelObjectMarker);
 
     let transform = Transform::from_xyz(lvl_width / 2.0 - 1., -0.5, lvl_width / 2.0 - 1.);
     if debug_settings.debug_render {
@@ -45,6 +50,7 @@ pub fn level_init(
                 shape.end.z as f32 - z_width / 2.0 + 0.5,
             )))
             .insert(RigidBody::Static)
+            .insert(LevelObjectMarker)
             .insert(CollisionShape::Cuboid {
                 half_extends: Vec3::new(x_width / 2.0, y_height / 2.0, z_width / 2.0),
                 border_radius: None,
@@ -20,6 +20,7 @@ use crate::creature::CreaturePlugin;
 use crate::level::LevelPlugin;
 use crate::particle::ParticlePlugin;
 use crate::player::PlayerPlugin;
+use crate::script::ScriptPlugin;
 use crate::ui::UIPlugin;
 use crate::util::component::despawn_outdated_entities;
 use bevy::diagnostic::LogDiagnosticsPlugin;
@@ -40,10 +41,14 @@ mod level;
 mod particle;
 mod physic_layers;
 mod player;
+mod script;
 mod system;
 mod ui;
 mod util;
 
+/// Acceleration of the free fall, down
+pub const GRAVITY: f32 = 10.;
+
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
 pub enum GameState {
     Playing,
@@ -66,7 +71,7 @@ fn main() {
         .add_plugin(HanabiPlugin)
         .add_plugin(LogDiagnosticsPlugin::default())
         .add_plugin(PhysicsPlugin::default()) // Add the plugin
-        .insert_resource(Gravity::from(Vec3::new(0.0, -10., 0.0)))
+        .insert_resource(Gravity::from(Vec3::new(0.0, -GRAVITY, 0.0)))
         // .add_plugin(DebugLinesPlugin::default())
         .add_plugin(AudioPlugin)
         .add_plugin(EguiPlugin)
@@ -76,7 +81,8 @@ fn main() {
         .add_plugin(UIPlugin)
         .add_plugin(GameAudioPlugin)
         .add_plugin(ParticlePlugin)
-        .add_startup_system(system::light::setup)
+        .add_plugin(ScriptPlugin)
+        .add_system(system::light::setup)
         .add_system(game_settings_save)
         .add_system(despawn_outdated_entities);
 
@@ -1,8 +1,11 @@
+use crate::creature::boss::BossPhaseChanged;
+use crate::creature::component::attack::event::WorldHit;
+use crate::GameState;
 use bevy::prelude::*;
 use bevy_hanabi::{
-    AccelModifier, ColorOverLifetimeModifier, EffectAsset, Gradient, ParticleEffectBundle,
-    ParticleLifetimeModifier, PositionSphereModifier, ShapeDimension, SizeOverLifetimeModifier,
-    Spawner,
+    AccelModifier, ColorOverLifetimeModifier, EffectAsset, Gradient, ParticleEffect,
+    ParticleEffectBundle, ParticleLifetimeModifier, PositionSphereModifier, ShapeDimension,
+    SizeOverLifetimeModifier, Spawner,
 };
 
 #[allow(clippy::module_name_repetitions)]
@@ -10,11 +13,22 @@ pub struct ParticlePlugin;
 
 impl Plugin for ParticlePlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(particle_setup);
+        app.add_startup_system(particle_setup)
+            .add_startup_system(particle_setup_boss_phase)
+            .add_startup_system(particle_setup_impact)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(particle_show_boss_phase)
+                    .with_system(particle_show_impacts),
+            );
     }
 }
 #[derive(Component)]
 pub struct PunchEffect;
+#[derive(Component)]
+pub struct BossPhaseEffect;
+#[derive(Component)]
+pub struct ImpactEffect;
 
 fn particle_setup(mut effects: ResMut<Assets<EffectAsset>>, mut commands: Commands) {
     let mut gradient = Gradient::new();
@@ -54,3 +68,100 @@ fn particle_setup(mut effects: ResMut<Assets<EffectAsset>>, mut commands: Comman
         .spawn_bundle(ParticleEffectBundle::new(effect))
         .insert(PunchEffect);
 }
+
+fn particle_setup_boss_phase(mut effects: ResMut<Assets<EffectAsset>>, mut commands: Commands) {
+    let mut gradient = Gradient::new();
+    gradient.add_key(0.0, Vec4::new(1., 0.8, 0.2, 1.));
+    gradient.add_key(0.5, Vec4::new(0.9, 0.1, 0., 1.));
+    gradient.add_key(1.0, Vec4::ZERO);
+
+    let mut size_gradient = Gradient::new();
+    size_gradient.add_key(0.0, Vec2::splat(0.4));
+    size_gradient.add_key(1.0, Vec2::splat(0.0));
+
+    let effect = effects.add(
+        EffectAsset {
+            name: "BossPhaseEffect".to_string(),
+            capacity: 300,
+            spawner: Spawner::once(200.0.into(), false),
+            ..Default::default()
+        }
+        .init(PositionSphereModifier {
+            center: Vec3::ZERO,
+            radius: 1.0,
+            dimension: ShapeDimension::Surface,
+            speed: 8.0.into(),
+        })
+        .init(ParticleLifetimeModifier { lifetime: 1.0 })
+        .update(AccelModifier {
+            accel: Vec3::new(0., 4., 0.),
+        })
+        .render(ColorOverLifetimeModifier { gradient })
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient,
+        }),
+    );
+
+    commands
+        .spawn_bundle(ParticleEffectBundle::new(effect))
+        .insert(BossPhaseEffect);
+}
+
+fn particle_show_boss_phase(
+    mut phase_events: EventReader<BossPhaseChanged>,
+    mut effects: Query<(&mut ParticleEffect, &mut Transform), With<BossPhaseEffect>>,
+) {
+    let (mut effect, mut transform) = effects.single_mut();
+    for event in phase_events.iter() {
+        transform.translation = event.position;
+        effect.maybe_spawner().unwrap().reset();
+    }
+}
+
+fn particle_setup_impact(mut effects: ResMut<Assets<EffectAsset>>, mut commands: Commands) {
+    let mut gradient = Gradient::new();
+    gradient.add_key(0.0, Vec4::new(0.9, 0.8, 0.6, 1.));
+    gradient.add_key(1.0, Vec4::new(0.4, 0.4, 0.4, 0.));
+
+    let mut size_gradient = Gradient::new();
+    size_gradient.add_key(0.0, Vec2::splat(0.1));
+    size_gradient.add_key(1.0, Vec2::splat(0.0));
+
+    let effect = effects.add(
+        EffectAsset {
+            name: "ImpactEffect".to_string(),
+            capacity: 100,
+            spawner: Spawner::once(15.0.into(), false),
+            ..Default::default()
+        }
+        .init(PositionSphereModifier {
+            center: Vec3::ZERO,
+            radius: 0.1,
+            dimension: ShapeDimension::Volume,
+            speed: 3.0.into(),
+        })
+        .init(ParticleLifetimeModifier { lifetime: 0.3 })
+        .update(AccelModifier {
+            accel: Vec3::new(0., -9.8, 0.),
+        })
+        .render(ColorOverLifetimeModifier { gradient })
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient,
+        }),
+    );
+
+    commands
+        .spawn_bundle(ParticleEffectBundle::new(effect))
+        .insert(ImpactEffect);
+}
+
+fn particle_show_impacts(
+    mut hit_events: EventReader<WorldHit>,
+    mut effects: Query<(&mut ParticleEffect, &mut Transform), With<ImpactEffect>>,
+) {
+    let (mut effect, mut transform) = effects.single_mut();
+    for hit in hit_events.iter() {
+        transform.translation = hit.position;
+        effect.maybe_spawner().unwrap().reset();
+    }
+}
@@ -1,14 +1,15 @@
-use crate::audio::DamageSoundType;
 use crate::creature::component::attack::event::DamageEvent;
 use crate::creature::component::hp::HP;
 use crate::creature::component::physiology_description::PhysiologyDescription;
+use crate::creature::component::faction::Faction;
 use crate::creature::component::CombatParameters;
-use crate::creature::EnemyCreatureMarker;
 use crate::player::PlayerMarker;
 use crate::{is_sensor, GamePhysicsLayer};
 use bevy::prelude::*;
 use heron::{CollisionEvent, CollisionLayers, CollisionShape, RigidBody};
-use rand::Rng;
+use std::time::Instant;
+
+pub mod ranged;
 
 #[derive(Component)]
 pub struct ThrustAttackSensor;
@@ -17,8 +18,10 @@ pub fn player_attack_thrust(
     keyboard_input: Res<Input<KeyCode>>,
     player: Query<(Entity, &CombatParameters, &PhysiologyDescription), With<PlayerMarker>>,
     mut commands: Commands,
+    mut ready_at: Local<Option<Instant>>,
 ) {
-    if !keyboard_input.just_pressed(KeyCode::O) {
+    let now = Instant::now();
+    if !keyboard_input.just_pressed(KeyCode::O) || ready_at.map_or(false, |at| now < at) {
         return;
     }
     let (e, combat, phys) = if let Ok(t) = player.get_single() {
@@ -26,6 +29,7 @@ pub fn player_attack_thrust(
     } else {
         return;
     };
+    *ready_at = Some(now + combat.cooldown);
 
     commands.entity(e).with_children(|parent| {
         parent
@@ -49,8 +53,8 @@ pub fn player_attack_thrust(
 pub fn player_attack_thrust_check_collisions(
     mut collision_events: EventReader<CollisionEvent>,
     thrust_sensors: Query<Entity, With<ThrustAttackSensor>>,
-    player: Query<&CombatParameters, With<PlayerMarker>>,
-    enemies: Query<&HP, With<EnemyCreatureMarker>>,
+    player: Query<(Entity, &CombatParameters), With<PlayerMarker>>,
+    targets: Query<&Faction, With<HP>>,
     mut commands: Commands,
     mut ev_damage: EventWriter<DamageEvent>,
 ) {
@@ -59,12 +63,11 @@ pub fn player_attack_thrust_check_collisions(
     } else {
         return;
     };
-    let combat = if let Ok(t) = player.get_single() {
+    let (player, combat) = if let Ok(t) = player.get_single() {
         t
     } else {
         return;
     };
-    let mut rng = rand::thread_rng();
 
     collision_events
         .iter()
@@ -73,10 +76,16 @@ pub fn player_attack_thrust_check_collisions(
             let (layers_1, layers_2) = event.collision_layers();
             let with_sensor = is_sensor(layers_1) || is_sensor(layers_2);
 
+            let can_be_hit = |entity| {
+                targets
+                    .get(entity)
+                    .map_or(false, |faction| faction.can_be_hit_by_player())
+            };
+
             if with_sensor {
-                if entity_1 == sensor && enemies.get(entity_2).is_ok() {
+                if entity_1 == sensor && can_be_hit(entity_2) {
                     return Some(entity_2);
-                } else if entity_2 == sensor && enemies.get(entity_1).is_ok() {
+                } else if entity_2 == sensor && can_be_hit(entity_1) {
                     return Some(entity_1);
                 };
             }
@@ -84,15 +93,14 @@ pub fn player_attack_thrust_check_collisions(
             None
         })
         .for_each(|target| {
-            let damage = rng.gen_range(
-                combat.base_damage - (combat.base_damage / 10)
-                    ..=combat.base_damage + (combat.base_damage / 10),
-            );
-
+            let (amount, critical) = combat.damage().roll();
             ev_damage.send(DamageEvent {
                 target,
-                amount: damage,
-                sound_type: DamageSoundType::Punch,
+                source: Some(player),
+                amount,
+                damage_type: combat.damage_type,
+                critical,
+                sound_type: combat.sound_type,
             });
         });
 
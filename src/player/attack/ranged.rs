@@ -0,0 +1,197 @@
+use crate::audio::DamageSoundType;
+use crate::creature::component::attack::component::{spawn_projectile, Attacker};
+use crate::creature::component::attack::damage::DamageRoll;
+use crate::creature::component::attack::shooting::projectile::Projectile;
+use crate::creature::component::faction::Faction;
+use crate::creature::component::physiology_description::PhysiologyDescription;
+use crate::player::PlayerMarker;
+use bevy::math::vec3;
+use bevy::prelude::*;
+use lib::entity::archetype::ProjectileParams;
+use lib::entity::weapon::RangedParams;
+use std::time::{Duration, Instant};
+
+// Projectiles appear a bit in front of the body, not inside of it
+const MUZZLE_OFFSET: f32 = 0.1;
+
+/// Gun of the player with a magazine to reload.
+#[derive(Component, Debug, Clone)]
+pub struct RangedWeapon {
+    pub damage: DamageRoll,
+    /// Distance per second
+    pub projectile_speed: f32,
+    pub projectile: ProjectileParams,
+    pub magazine: u16,
+    pub cooldown: Duration,
+    pub reload_time: Duration,
+    pub sound_type: DamageSoundType,
+    ammo: u16,
+    ready_at: Instant,
+    reloaded_at: Option<Instant>,
+}
+
+impl RangedWeapon {
+    /// Loaded, ready to shoot.
+    pub fn new(params: &RangedParams, sound_type: DamageSoundType) -> Self {
+        RangedWeapon {
+            damage: DamageRoll {
+                base: params.damage,
+                damage_type: params.damage_type,
+                crit_chance: params.crit_chance,
+            },
+            projectile_speed: params.projectile_speed,
+            projectile: params.projectile,
+            magazine: params.magazine,
+            cooldown: Duration::from_secs_f32(params.cooldown.max(0.)),
+            reload_time: Duration::from_secs_f32(params.reload_time.max(0.)),
+            sound_type,
+            ammo: params.magazine,
+            ready_at: Instant::now(),
+            reloaded_at: None,
+        }
+    }
+
+    pub fn ammo(&self) -> u16 {
+        self.ammo
+    }
+
+    pub fn is_reloading(&self) -> bool {
+        self.reloaded_at.is_some()
+    }
+
+    /// Takes a round if the gun is ready, the empty magazine starts reloading.
+    pub fn fire(&mut self, now: Instant) -> bool {
+        if self.is_reloading() || now < self.ready_at {
+            return false;
+        }
+        if self.ammo == 0 {
+            self.reload(now);
+            return false;
+        }
+
+        self.ammo -= 1;
+        self.ready_at = now + self.cooldown;
+        if self.ammo == 0 {
+            self.reload(now);
+        }
+        true
+    }
+
+    pub fn reload(&mut self, now: Instant) {
+        if !self.is_reloading() && self.ammo < self.magazine {
+            self.reloaded_at = Some(now + self.reload_time);
+        }
+    }
+
+    /// Fills the magazine once the reload is over.
+    pub fn update(&mut self, now: Instant) {
+        if self.reloaded_at.map_or(false, |at| at <= now) {
+            self.ammo = self.magazine;
+            self.reloaded_at = None;
+        }
+    }
+}
+
+/// Horizontal direction the camera looks in, the body facing if it looks straight down.
+pub fn aim_direction(camera_forward: Vec3, facing: Vec3) -> Vec3 {
+    vec3(camera_forward.x, 0., camera_forward.z)
+        .try_normalize()
+        .unwrap_or_else(|| vec3(facing.x, 0., facing.z).normalize_or_zero())
+}
+
+/// Shoots the way the camera looks, so the aim doesn't depend on where the body turned.
+pub fn player_attack_shoot(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    camera: Query<&Transform, (With<Camera3d>, Without<PlayerMarker>)>,
+    mut player: Query<
+        (
+            Entity,
+            &Transform,
+            &Faction,
+            &PhysiologyDescription,
+            &mut RangedWeapon,
+        ),
+        With<PlayerMarker>,
+    >,
+) {
+    let (entity, transform, faction, phys, mut weapon) = if let Ok(t) = player.get_single_mut() {
+        t
+    } else {
+        return;
+    };
+    let now = Instant::now();
+    weapon.update(now);
+
+    if keyboard_input.just_pressed(KeyCode::R) {
+        weapon.reload(now);
+    }
+    if !keyboard_input.pressed(KeyCode::P) || !weapon.fire(now) {
+        return;
+    }
+
+    let facing = transform.rotation * Vec3::Z;
+    let camera_forward = camera
+        .get_single()
+        .map_or(facing, |camera| camera.forward());
+    let direction = aim_direction(camera_forward, facing);
+    let attacker = Attacker {
+        entity,
+        faction: *faction,
+        position: transform.translation,
+        eyes: transform.translation + direction * (phys.model_radius + MUZZLE_OFFSET),
+        model_radius: phys.model_radius,
+    };
+    let projectile = Projectile::new(
+        direction,
+        weapon.projectile_speed,
+        weapon.damage,
+        entity,
+        weapon.projectile,
+    )
+    .with_sound(weapon.sound_type);
+    spawn_projectile(&mut commands, &mut meshes, &attacker, projectile);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weapon_reloads_empty_magazine() {
+        let start = Instant::now();
+        let params = RangedParams {
+            magazine: 2,
+            ..Default::default()
+        };
+        let mut weapon = RangedWeapon::new(&params, DamageSoundType::Bullet);
+        weapon.ready_at = start;
+
+        assert!(weapon.fire(start));
+        assert!(!weapon.fire(start));
+        let ready = start + weapon.cooldown;
+        assert!(weapon.fire(ready));
+        assert_eq!(weapon.ammo(), 0);
+        assert!(weapon.is_reloading());
+
+        let reloaded = ready + weapon.reload_time;
+        weapon.update(reloaded - Duration::from_millis(1));
+        assert!(!weapon.fire(reloaded - Duration::from_millis(1)));
+        weapon.update(reloaded);
+        assert_eq!(weapon.ammo(), 2);
+        assert!(weapon.fire(reloaded));
+    }
+
+    #[test]
+    fn aim_follows_the_camera_on_the_ground() {
+        let facing = Vec3::X;
+        let camera_forward = vec3(1., -1., 1.).normalize();
+        let aim = aim_direction(camera_forward, facing);
+        assert!(aim.abs_diff_eq(vec3(1., 0., 1.).normalize(), 1e-5));
+
+        let straight_down = -Vec3::Y;
+        assert_eq!(aim_direction(straight_down, facing), Vec3::X);
+        assert_eq!(aim_direction(straight_down, Vec3::Y), Vec3::ZERO);
+    }
+}
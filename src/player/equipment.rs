@@ -0,0 +1,203 @@
+use crate::audio::DamageSoundType;
+use crate::creature::component::CombatParameters;
+use crate::player::attack::ranged::RangedWeapon;
+use crate::player::PlayerMarker;
+use bevy::prelude::*;
+use lib::entity::weapon::{RangedParams, WeaponId, Weapons};
+use std::collections::HashMap;
+
+// Leaf bone of the right hand in player.glb, the weapon mesh is its only child
+const HAND_NODE: &str = "Hand.R";
+// The number keys take the carried weapons in this order
+const SLOT_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Hand bone of the player model the weapon mesh is attached to.
+#[derive(Component, Debug)]
+pub struct WeaponSocket;
+
+/// Weapons the player carries and the one in the hand, bare hands if none.
+#[derive(Component, Debug)]
+pub struct Equipment {
+    carried: Vec<WeaponId>,
+    equipped: Option<WeaponId>,
+    /// Weapon the components of the player were made for
+    applied: Option<WeaponId>,
+    /// Guns put away keep their magazine, the one in the hand is the `RangedWeapon` component
+    holstered: HashMap<WeaponId, RangedWeapon>,
+}
+
+impl Equipment {
+    /// The first carried weapon is taken in the hand.
+    pub fn new(carried: Vec<WeaponId>) -> Self {
+        Equipment {
+            equipped: carried.first().copied(),
+            carried,
+            applied: None,
+            holstered: HashMap::default(),
+        }
+    }
+
+    pub fn equipped(&self) -> Option<WeaponId> {
+        self.equipped
+    }
+
+    /// Takes the weapon of the slot, the same slot again puts it away.
+    pub fn toggle_slot(&mut self, slot: usize) {
+        if let Some(weapon) = self.carried.get(slot).copied() {
+            self.equipped = (self.equipped != Some(weapon)).then_some(weapon);
+        }
+    }
+
+    /// Puts the gun away as it is, a started reload goes on meanwhile.
+    pub fn holster(&mut self, weapon: WeaponId, ranged: RangedWeapon) {
+        self.holstered.insert(weapon, ranged);
+    }
+
+    /// The gun as it was put away, loaded if it's taken for the first time.
+    pub fn draw(&mut self, weapon: WeaponId, params: &RangedParams) -> RangedWeapon {
+        self.holstered
+            .remove(&weapon)
+            .unwrap_or_else(|| RangedWeapon::new(params, DamageSoundType::Weapon(weapon)))
+    }
+}
+
+pub fn equipment_switch_weapons(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut player: Query<&mut Equipment, With<PlayerMarker>>,
+) {
+    let mut equipment = if let Ok(e) = player.get_single_mut() {
+        e
+    } else {
+        return;
+    };
+
+    for (slot, key) in SLOT_KEYS.iter().enumerate() {
+        if keyboard_input.just_pressed(*key) {
+            equipment.toggle_slot(slot);
+        }
+    }
+}
+
+/// The hand appears only once the scene of the player model is spawned.
+pub fn equipment_find_hand(
+    mut commands: Commands,
+    nodes: Query<(Entity, &Name), Added<Name>>,
+    parents: Query<&Parent>,
+    mut player: Query<&mut Equipment, With<PlayerMarker>>,
+) {
+    for (entity, name) in nodes.iter() {
+        if name.as_str() != HAND_NODE {
+            continue;
+        }
+        let mut ancestor = entity;
+        while let Ok(parent) = parents.get(ancestor) {
+            ancestor = parent.get();
+            if let Ok(mut equipment) = player.get_mut(ancestor) {
+                commands.entity(entity).insert(WeaponSocket);
+                // Puts the weapon already in the hand on the new socket
+                equipment.set_changed();
+                break;
+            }
+        }
+    }
+}
+
+/// The weapon in the hand decides how the player strikes and shoots.
+pub fn equipment_apply_weapon(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    weapons: Res<Weapons>,
+    mut player: Query<
+        (Entity, &mut Equipment, Option<&RangedWeapon>),
+        (With<PlayerMarker>, Changed<Equipment>),
+    >,
+    sockets: Query<Entity, With<WeaponSocket>>,
+) {
+    let (entity, mut equipment, current) = if let Ok(t) = player.get_single_mut() {
+        t
+    } else {
+        return;
+    };
+    if let (Some(previous), Some(current)) = (equipment.applied, current) {
+        equipment.holster(previous, current.clone());
+    }
+    equipment.applied = equipment.equipped();
+    let weapon = equipment.equipped().map(|id| (id, weapons.get(id)));
+
+    let mut player_commands = commands.entity(entity);
+    let combat = weapon.and_then(|(id, weapon)| {
+        let melee = weapon.melee.as_ref()?;
+        let sound_type = DamageSoundType::Weapon(id);
+        Some(CombatParameters::from_melee(melee, sound_type))
+    });
+    player_commands.insert(combat.unwrap_or_default());
+    let ranged = weapon.and_then(|(id, weapon)| Some(equipment.draw(id, weapon.ranged.as_ref()?)));
+    if let Some(ranged) = ranged {
+        player_commands.insert(ranged);
+    } else {
+        player_commands.remove::<RangedWeapon>();
+    }
+
+    if let Ok(socket) = sockets.get_single() {
+        let mut socket_commands = commands.entity(socket);
+        socket_commands.despawn_descendants();
+        if let Some(mesh) = weapon.and_then(|(_, weapon)| weapon.mesh.as_ref()) {
+            let scene = asset_server.load(mesh.as_str());
+            socket_commands.with_children(|builder| {
+                builder.spawn_bundle(SceneBundle {
+                    scene,
+                    ..Default::default()
+                });
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn slots_toggle_weapons() {
+        let weapons = Weapons::from_dir("assets/weapon");
+        let sword = weapons.find("Sword").unwrap();
+        let pistol = weapons.find("Pistol").unwrap();
+        let mut equipment = Equipment::new(vec![sword, pistol]);
+        assert_eq!(equipment.equipped(), Some(sword));
+
+        equipment.toggle_slot(1);
+        assert_eq!(equipment.equipped(), Some(pistol));
+        equipment.toggle_slot(5);
+        assert_eq!(equipment.equipped(), Some(pistol));
+        equipment.toggle_slot(1);
+        assert_eq!(equipment.equipped(), None);
+    }
+
+    #[test]
+    fn holstered_guns_keep_their_magazine() {
+        let weapons = Weapons::from_dir("assets/weapon");
+        let pistol = weapons.find("Pistol").unwrap();
+        let params = weapons.get(pistol).ranged.as_ref().unwrap();
+        let mut equipment = Equipment::new(vec![pistol]);
+
+        let mut gun = equipment.draw(pistol, params);
+        assert_eq!(gun.ammo(), params.magazine);
+        assert!(gun.fire(Instant::now() + Duration::from_secs(1)));
+        equipment.holster(pistol, gun);
+
+        let gun = equipment.draw(pistol, params);
+        assert_eq!(gun.ammo(), params.magazine - 1);
+        assert_eq!(equipment.draw(pistol, params).ammo(), params.magazine);
+    }
+}
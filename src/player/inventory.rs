@@ -0,0 +1,258 @@
+use crate::creature::component::attack::event::HealEvent;
+use crate::creature::death::LootDrop;
+use crate::player::PlayerMarker;
+use bevy::prelude::*;
+use heron::CollisionEvent;
+use lib::entity::item::{ItemKind, ItemStack, Items};
+use lib::util::save::SaveGame;
+use std::collections::HashSet;
+
+pub const SAVE_FILE: &str = "save.json";
+// Different stacks the player can carry at once
+const CAPACITY: usize = 20;
+
+/// Items the player carries, a slot holds up to `max_stack` of one item.
+#[derive(Component, Debug, Default)]
+pub struct Inventory {
+    stacks: Vec<ItemStack>,
+}
+
+impl Inventory {
+    /// Stacks of the save, the empty ones are dropped and the ones over the capacity are lost.
+    pub fn from_stacks(stacks: Vec<ItemStack>) -> Self {
+        let mut stacks: Vec<ItemStack> = stacks.into_iter().filter(|s| s.count > 0).collect();
+        if stacks.len() > CAPACITY {
+            warn!(
+                "{} stacks don't fit in the inventory",
+                stacks.len() - CAPACITY
+            );
+            stacks.truncate(CAPACITY);
+        }
+        Inventory { stacks }
+    }
+
+    pub fn stacks(&self) -> &[ItemStack] {
+        &self.stacks
+    }
+
+    pub fn count(&self, item: &str) -> u16 {
+        self.stacks
+            .iter()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// Whether at least one of the item fits, doesn't touch the inventory.
+    pub fn has_room(&self, item: &str, max_stack: u16) -> bool {
+        self.stacks.len() < CAPACITY
+            || self
+                .stacks
+                .iter()
+                .any(|stack| stack.item == item && stack.count < max_stack.max(1))
+    }
+
+    /// Fills the stacks of the item first, returns how many didn't fit.
+    pub fn add(&mut self, item: &str, count: u16, max_stack: u16) -> u16 {
+        let max_stack = max_stack.max(1);
+        let mut left = count;
+        for stack in self.stacks.iter_mut().filter(|stack| stack.item == item) {
+            let added = left.min(max_stack.saturating_sub(stack.count));
+            stack.count += added;
+            left -= added;
+        }
+        while left > 0 && self.stacks.len() < CAPACITY {
+            let added = left.min(max_stack);
+            self.stacks.push(ItemStack {
+                item: item.to_string(),
+                count: added,
+            });
+            left -= added;
+        }
+        left
+    }
+
+    /// Takes one item of the slot, the emptied slot disappears.
+    pub fn take(&mut self, slot: usize) -> Option<String> {
+        let stack = self.stacks.get_mut(slot)?;
+        stack.count = stack.count.checked_sub(1)?;
+        let item = stack.item.clone();
+        if stack.count == 0 {
+            self.stacks.remove(slot);
+        }
+        Some(item)
+    }
+}
+
+/// Consumes one item of the slot, returns the HP to heal. Items which can't be used stay.
+pub fn use_item(inventory: &mut Inventory, slot: usize, items: &Items) -> Option<u16> {
+    let stack = inventory.stacks().get(slot)?;
+    match items.get(&stack.item)?.kind {
+        ItemKind::Consumable { heal } => {
+            inventory.take(slot);
+            Some(heal)
+        }
+        ItemKind::Misc => None,
+    }
+}
+
+/// Loot is picked up while the player touches it, what doesn't fit waits there for a free slot.
+pub fn inventory_pick_up(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    items: Res<Items>,
+    mut touched: Local<HashSet<Entity>>,
+    mut drops: Query<&mut LootDrop>,
+    mut player: Query<(Entity, &mut Inventory), With<PlayerMarker>>,
+) {
+    let (player, mut inventory) = if let Ok(t) = player.get_single_mut() {
+        t
+    } else {
+        return;
+    };
+
+    for event in collision_events.iter() {
+        let (entity_1, entity_2) = event.rigid_body_entities();
+        let entity = if entity_1 == player {
+            entity_2
+        } else if entity_2 == player {
+            entity_1
+        } else {
+            continue;
+        };
+        let drop = if let Ok(d) = drops.get(entity) {
+            d
+        } else {
+            continue;
+        };
+
+        if event.is_started() {
+            if items.get(&drop.item).is_some() {
+                touched.insert(entity);
+            } else {
+                warn!("Unknown item {} can't be picked up", drop.item);
+            }
+        } else {
+            touched.remove(&entity);
+        }
+    }
+    touched.retain(|entity| drops.get(*entity).is_ok());
+
+    for entity in touched.iter() {
+        let mut drop = if let Ok(d) = drops.get_mut(*entity) {
+            d
+        } else {
+            continue;
+        };
+        let item = if let Some(i) = items.get(&drop.item) {
+            i
+        } else {
+            continue;
+        };
+        // A full inventory isn't touched, otherwise it would be saved every frame
+        if !inventory.has_room(&item.name, item.max_stack) {
+            continue;
+        }
+
+        drop.count = inventory.add(&item.name, drop.count, item.max_stack);
+        if drop.count == 0 {
+            commands.entity(*entity).despawn_recursive();
+        }
+    }
+}
+
+/// `H` uses the first consumable without opening the inventory.
+pub fn inventory_use_hotkey(
+    keyboard_input: Res<Input<KeyCode>>,
+    items: Res<Items>,
+    mut heal_events: EventWriter<HealEvent>,
+    mut player: Query<(Entity, &mut Inventory), With<PlayerMarker>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::H) {
+        return;
+    }
+    let (entity, mut inventory) = if let Ok(t) = player.get_single_mut() {
+        t
+    } else {
+        return;
+    };
+
+    let slot = inventory.stacks().iter().position(|stack| {
+        items.get(&stack.item).map_or(false, |item| {
+            matches!(item.kind, ItemKind::Consumable { .. })
+        })
+    });
+    if let Some(amount) = slot.and_then(|slot| use_item(&mut inventory, slot, &items)) {
+        heal_events.send(HealEvent {
+            target: entity,
+            amount,
+        });
+    }
+}
+
+/// Every change of the inventory is written to the save file.
+pub fn inventory_save(
+    mut save: ResMut<SaveGame>,
+    player: Query<&Inventory, (With<PlayerMarker>, Changed<Inventory>)>,
+) {
+    if let Ok(inventory) = player.get_single() {
+        save.inventory = inventory.stacks().to_vec();
+        save.save(SAVE_FILE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_stack_up_to_the_limit() {
+        let mut inventory = Inventory::default();
+        assert_eq!(inventory.add("pizza_slice", 7, 5), 0);
+        assert_eq!(inventory.add("healing_potion", 1, 5), 0);
+        assert_eq!(inventory.add("pizza_slice", 4, 5), 0);
+        assert_eq!(inventory.count("pizza_slice"), 11);
+        let counts: Vec<u16> = inventory.stacks().iter().map(|s| s.count).collect();
+        assert_eq!(counts, vec![5, 5, 1, 1]);
+
+        assert_eq!(inventory.add("bone", 1000, 10), 1000 - 16 * 10);
+        assert_eq!(inventory.stacks().len(), CAPACITY);
+        assert!(inventory.has_room("pizza_slice", 5));
+        assert!(!inventory.has_room("healing_potion", 1));
+        assert!(!inventory.has_room("bone", 10));
+    }
+
+    #[test]
+    fn saved_stacks_are_sanitized() {
+        let stack = |item: &str, count| ItemStack {
+            item: item.to_string(),
+            count,
+        };
+        let mut stacks = vec![stack("bone", 0), stack("pizza_slice", 2)];
+        stacks.extend((0..CAPACITY).map(|_| stack("healing_potion", 1)));
+
+        let mut inventory = Inventory::from_stacks(stacks);
+        assert_eq!(inventory.stacks().len(), CAPACITY);
+        assert_eq!(inventory.stacks()[0], stack("pizza_slice", 2));
+        assert_eq!(inventory.count("bone"), 0);
+
+        inventory.stacks[0].count = 0;
+        assert_eq!(inventory.take(0), None);
+        assert_eq!(inventory.take(1), Some("healing_potion".to_string()));
+        assert_eq!(inventory.stacks().len(), CAPACITY - 1);
+    }
+
+    #[test]
+    fn consumables_are_used_up() {
+        let items = Items::from_dir("assets/item");
+        let mut inventory = Inventory::default();
+        inventory.add("healing_potion", 2, 5);
+        inventory.add("bone", 1, 10);
+
+        assert_eq!(use_item(&mut inventory, 0, &items), Some(50));
+        assert_eq!(use_item(&mut inventory, 0, &items), Some(50));
+        assert!(inventory.stacks().iter().all(|s| s.item == "bone"));
+        assert_eq!(use_item(&mut inventory, 0, &items), None);
+        assert_eq!(use_item(&mut inventory, 3, &items), None);
+    }
+}
@@ -5,21 +5,44 @@ use bevy::prelude::*;
 use heron::prelude::*;
 
 use crate::creature::buffs::BuffStorage;
+use crate::creature::component::attack::event::Died;
+use crate::creature::component::faction::Faction;
 use crate::creature::component::CombatParameters;
+use crate::level::SwitchLevelEvent;
 use crate::player::animation::{
     animation_rotate_model_on_move, animation_run_on_move, player_animation_setup,
 };
+use crate::player::attack::ranged::player_attack_shoot;
 use crate::player::attack::{
     player_attack_thrust, player_attack_thrust_check_collisions, ThrustAttackSensor,
 };
+use crate::player::equipment::{
+    equipment_apply_weapon, equipment_find_hand, equipment_switch_weapons, Equipment,
+};
+use crate::player::inventory::{
+    inventory_pick_up, inventory_save, inventory_use_hotkey, Inventory, SAVE_FILE,
+};
 use crate::player::system::camera::CameraPlugin;
 use crate::player::system::keyboard_interaction::player_track_keyboard_interaction;
 use crate::{GamePhysicsLayer, GameState};
+use lib::entity::item::Items;
+use lib::entity::level::Level;
+use lib::entity::weapon::Weapons;
+use lib::util::save::SaveGame;
 
 mod animation;
-mod attack;
+pub mod attack;
+pub mod equipment;
+pub mod inventory;
 pub mod system;
 
+// TODO take spawn point from world file/save file
+pub const SPAWN_POINT: Vec3 = Vec3::new(4., 2., 7.);
+// TODO read from save file
+const STARTING_WEAPONS: [&str; 3] = ["Sword", "Pistol", "Fire staff"];
+const WEAPON_DIR: &str = "assets/weapon";
+const ITEM_DIR: &str = "assets/item";
+
 #[derive(Component, Debug)]
 pub struct PlayerMarker;
 
@@ -29,29 +52,50 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(CameraPlugin)
+            .insert_resource(Weapons::from_dir(WEAPON_DIR))
+            .insert_resource(Items::from_dir(ITEM_DIR))
+            .insert_resource(SaveGame::from_file(SAVE_FILE))
             .add_startup_system(player_setup)
             .add_startup_system(player_animation_setup)
+            // Commands of it are applied before the weapon is put in the hand
+            .add_system_to_stage(CoreStage::PreUpdate, equipment_find_hand)
             .add_system_set(
                 SystemSet::on_update(GameState::Playing)
                     .with_system(player_track_keyboard_interaction)
                     .with_system(animation_run_on_move)
                     .with_system(animation_rotate_model_on_move)
                     .with_system(player_attack_thrust)
-                    .with_system(player_attack_thrust_check_collisions),
-            );
+                    .with_system(player_attack_thrust_check_collisions)
+                    .with_system(player_attack_shoot)
+                    .with_system(equipment_switch_weapons)
+                    .with_system(equipment_apply_weapon)
+                    .with_system(inventory_pick_up)
+                    .with_system(inventory_use_hotkey)
+                    .with_system(player_respawn_on_death),
+            )
+            // The inventory changes in the pause menu as well
+            .add_system(inventory_save);
     }
 }
 
-pub fn player_setup(asset_server: Res<AssetServer>, mut commands: Commands) {
+pub fn player_setup(
+    asset_server: Res<AssetServer>,
+    weapons: Res<Weapons>,
+    save: Res<SaveGame>,
+    mut commands: Commands,
+) {
     let scene = asset_server.load("mesh/player.glb#Scene0");
     let comb = CombatParameters::default();
     let phys = PhysiologyDescription::default();
+    let carried = STARTING_WEAPONS
+        .iter()
+        .filter_map(|name| weapons.find(name))
+        .collect();
 
     commands
         .spawn_bundle(SceneBundle {
             scene,
-            // TODO take spawn point from world file/save file
-            transform: Transform::from_xyz(4., 2., 7.),
+            transform: Transform::from_translation(SPAWN_POINT),
             ..Default::default()
         })
         .with_children(|parent| {
@@ -73,12 +117,10 @@ pub fn player_setup(asset_server: Res<AssetServer>, mut commands: Commands) {
                 );
         })
         .insert(PlayerMarker)
+        .insert(Faction::Player)
         .insert(BuffStorage::<PhysiologyDescription>::new())
         .insert(RigidBody::Dynamic)
-        .insert(CollisionShape::Cylinder {
-            radius: 0.5,
-            half_height: 1.0,
-        })
+        .insert(phys.collision_shape())
         .insert(Velocity::from_linear(vec3(0., 0., 0.)))
         .insert(Acceleration::from_linear(Vec3::X * 1.0))
         .insert(RotationConstraints::lock())
@@ -87,6 +129,25 @@ pub fn player_setup(asset_server: Res<AssetServer>, mut commands: Commands) {
         )
         .insert(phys)
         .insert(comb)
+        .insert(Equipment::new(carried))
+        .insert(Inventory::from_stacks(save.inventory.clone()))
         // TODO read from save file
         .insert(HP::full(100));
 }
+
+fn player_respawn_on_death(
+    mut died_events: EventReader<Died>,
+    mut switch_events: EventWriter<SwitchLevelEvent>,
+    level: Res<Level>,
+    mut player: Query<&mut HP, With<PlayerMarker>>,
+) {
+    for event in died_events.iter() {
+        if let Ok(mut hp) = player.get_mut(event.entity) {
+            hp.restore();
+            // Reloads the level so the fight starts over, the player is moved to the spawn point
+            switch_events.send(SwitchLevelEvent {
+                name: level.name.clone(),
+            });
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::math::{vec3, Vec3};
+use bevy::prelude::info;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Dynamic, Engine, FuncArgs, Scope, AST};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+// Budget for a single hook call, infinite loops in a level script must not freeze the game
+const MAX_OPERATIONS: u64 = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptCommand {
+    SpawnEnemy { name: String, position: Vec3 },
+    SpawnNeutral { name: String, position: Vec3 },
+    DamagePlayer { amount: u16 },
+    HealPlayer { amount: u16 },
+    DamageCreatures { name: String, amount: u16 },
+    PlaySound(String),
+    SwitchLevel(String),
+}
+
+/// Snapshot of the game state which is readable from the script.
+#[derive(Default, Debug)]
+pub struct ScriptView {
+    pub level_name: String,
+    pub is_day: bool,
+    pub alive_creatures: HashMap<String, usize>,
+    pub player_position: Option<Vec3>,
+}
+
+/// Sandboxed level logic.
+///
+/// A script consists only of functions, all of them are optional:
+/// - `init()` returns the initial state of the script
+/// - `on_tick(state, dt)` is called every frame and returns the new state
+pub struct LevelScript {
+    engine: Engine,
+    ast: AST,
+    state: Dynamic,
+    commands: Arc<Mutex<Vec<ScriptCommand>>>,
+    view: Arc<RwLock<ScriptView>>,
+}
+
+impl LevelScript {
+    pub fn compile(source: &str) -> Result<LevelScript, String> {
+        let commands = Arc::new(Mutex::new(vec![]));
+        let view = Arc::new(RwLock::new(ScriptView::default()));
+        let engine = create_engine(&commands, &view);
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+
+        Ok(LevelScript {
+            engine,
+            ast,
+            state: Dynamic::UNIT,
+            commands,
+            view,
+        })
+    }
+
+    pub fn start(&mut self) -> Result<(), String> {
+        self.call_hook("init", 0, ())
+    }
+
+    pub fn tick(&mut self, dt: f32) -> Result<(), String> {
+        let state = self.state.clone();
+        self.call_hook("on_tick", 2, (state, f64::from(dt)))
+    }
+
+    pub fn update_view(&self, view: ScriptView) {
+        *self.view.write().unwrap() = view;
+    }
+
+    pub fn take_commands(&self) -> Vec<ScriptCommand> {
+        self.commands.lock().unwrap().drain(..).collect()
+    }
+
+    fn call_hook(&mut self, name: &str, arity: usize, args: impl FuncArgs) -> Result<(), String> {
+        let defined = self
+            .ast
+            .iter_functions()
+            .any(|f| f.name == name && f.params.len() == arity);
+        if !defined {
+            return Ok(());
+        }
+
+        let mut scope = Scope::new();
+        self.state = self
+            .engine
+            .call_fn::<Dynamic>(&mut scope, &self.ast, name, args)
+            .map_err(|e| format!("`{name}` failed: {e}"))?;
+
+        Ok(())
+    }
+}
+
+fn create_engine(
+    commands: &Arc<Mutex<Vec<ScriptCommand>>>,
+    view: &Arc<RwLock<ScriptView>>,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(1024);
+    engine.set_max_array_size(1024);
+    engine.set_max_map_size(256);
+    engine.disable_symbol("eval");
+    engine.on_print(|text| info!("[level script] {text}"));
+
+    let v = view.clone();
+    engine.register_fn("level_name", move || v.read().unwrap().level_name.clone());
+    let v = view.clone();
+    engine.register_fn("is_day", move || v.read().unwrap().is_day);
+    let v = view.clone();
+    engine.register_fn("creatures_alive", move |name: &str| {
+        v.read()
+            .unwrap()
+            .alive_creatures
+            .get(name)
+            .map_or(0, |amount| *amount as i64)
+    });
+    let v = view.clone();
+    engine.register_fn("player_distance", move |x: f64, y: f64, z: f64| {
+        v.read().unwrap().player_position.map_or(-1.0, |position| {
+            f64::from(position.distance(vec3(x as f32, y as f32, z as f32)))
+        })
+    });
+
+    let c = commands.clone();
+    engine.register_fn("spawn_enemy", move |name: &str, x: f64, y: f64, z: f64| {
+        c.lock().unwrap().push(ScriptCommand::SpawnEnemy {
+            name: name.to_string(),
+            position: vec3(x as f32, y as f32, z as f32),
+        });
+    });
+    let c = commands.clone();
+    engine.register_fn("spawn_neutral", move |name: &str, x: f64, y: f64, z: f64| {
+        c.lock().unwrap().push(ScriptCommand::SpawnNeutral {
+            name: name.to_string(),
+            position: vec3(x as f32, y as f32, z as f32),
+        });
+    });
+    let c = commands.clone();
+    engine.register_fn("damage_player", move |amount: i64| {
+        c.lock().unwrap().push(ScriptCommand::DamagePlayer {
+            amount: amount.clamp(0, i64::from(u16::MAX)) as u16,
+        });
+    });
+    let c = commands.clone();
+    engine.register_fn("heal_player", move |amount: i64| {
+        c.lock().unwrap().push(ScriptCommand::HealPlayer {
+            amount: amount.clamp(0, i64::from(u16::MAX)) as u16,
+        });
+    });
+    let c = commands.clone();
+    engine.register_fn("damage_creatures", move |name: &str, amount: i64| {
+        c.lock().unwrap().push(ScriptCommand::DamageCreatures {
+            name: name.to_string(),
+            amount: amount.clamp(0, i64::from(u16::MAX)) as u16,
+        });
+    });
+    let c = commands.clone();
+    engine.register_fn("play_sound", move |sound: &str| {
+        c.lock()
+            .unwrap()
+            .push(ScriptCommand::PlaySound(sound.to_string()));
+    });
+    let c = commands.clone();
+    engine.register_fn("switch_level", move |name: &str| {
+        c.lock()
+            .unwrap()
+            .push(ScriptCommand::SwitchLevel(name.to_string()));
+    });
+
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawns_wave_when_all_creatures_are_dead() {
+        let mut script = LevelScript::compile(
+            r#"
+            fn init() { #{ spawned: false } }
+            fn on_tick(state, dt) {
+                if !state.spawned && creatures_alive("Pizza") == 0 {
+                    spawn_enemy("Pizza", 1.0, 2.0, 3.0);
+                    state.spawned = true;
+                }
+                state
+            }
+            "#,
+        )
+        .unwrap();
+        script.start().unwrap();
+
+        script.update_view(ScriptView {
+            alive_creatures: HashMap::from([("Pizza".to_string(), 1)]),
+            ..Default::default()
+        });
+        script.tick(0.1).unwrap();
+        assert!(script.take_commands().is_empty());
+
+        script.update_view(ScriptView::default());
+        script.tick(0.1).unwrap();
+        script.tick(0.1).unwrap();
+        assert_eq!(
+            script.take_commands(),
+            vec![ScriptCommand::SpawnEnemy {
+                name: "Pizza".to_string(),
+                position: vec3(1.0, 2.0, 3.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn endless_script_is_interrupted() {
+        let mut script = LevelScript::compile("fn on_tick(state, dt) { loop {} }").unwrap();
+
+        assert!(script.tick(0.1).is_err());
+    }
+}
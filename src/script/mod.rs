@@ -0,0 +1,143 @@
+use crate::audio::{DamageSoundType, SoundEvent, SoundLayer, SoundType};
+use crate::creature::component::attack::event::{DamageEvent, HealEvent};
+use crate::creature::death::Dying;
+use crate::creature::{spawn_creature, CreatureKind};
+use crate::level::reader::read_level_script;
+use crate::level::SwitchLevelEvent;
+use crate::player::PlayerMarker;
+use crate::script::engine::{LevelScript, ScriptCommand, ScriptView};
+use crate::GameState;
+use bevy::prelude::*;
+use lib::entity::archetype::CreatureArchetypes;
+use lib::entity::damage::DamageType;
+use lib::entity::level::creature::{Creature, CreatureName};
+use lib::entity::level::Level;
+use std::collections::HashMap;
+
+pub mod engine;
+
+#[allow(clippy::module_name_repetitions)]
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(script_load).add_system_set(
+            SystemSet::on_update(GameState::Playing).with_system(script_tick),
+        );
+    }
+}
+
+fn script_load(mut commands: Commands, level: Res<Level>) {
+    if !level.is_changed() {
+        return;
+    }
+    commands.remove_resource::<LevelScript>();
+
+    if let Some(source) = read_level_script(&level.name) {
+        match LevelScript::compile(&source).and_then(|mut script| {
+            script.start()?;
+            Ok(script)
+        }) {
+            Ok(script) => commands.insert_resource(script),
+            Err(e) => error!("Cannot load script of level {}: {e}", level.name),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn script_tick(
+    time: Res<Time>,
+    level: Res<Level>,
+    asset_server: Res<AssetServer>,
+    archetypes: Res<CreatureArchetypes>,
+    script: Option<ResMut<LevelScript>>,
+    creatures: Query<(Entity, &CreatureKind), Without<Dying>>,
+    player: Query<(Entity, &Transform), With<PlayerMarker>>,
+    mut commands: Commands,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut heal_events: EventWriter<HealEvent>,
+    mut sound_events: EventWriter<SoundEvent>,
+    mut switch_events: EventWriter<SwitchLevelEvent>,
+) {
+    let mut script = if let Some(script) = script {
+        script
+    } else {
+        return;
+    };
+
+    let mut alive_creatures = HashMap::new();
+    for (_, kind) in creatures.iter() {
+        *alive_creatures.entry(kind.0.to_string()).or_insert(0) += 1;
+    }
+    script.update_view(ScriptView {
+        level_name: level.name.clone(),
+        is_day: level.is_day(),
+        alive_creatures,
+        player_position: player.iter().next().map(|(_, t)| t.translation),
+    });
+
+    if let Err(e) = script.tick(time.delta_seconds()) {
+        error!("Level script of {}: {e}", level.name);
+    }
+
+    for command in script.take_commands() {
+        match command {
+            ScriptCommand::SpawnEnemy { name, position } => {
+                let creature = Creature::enemy(CreatureName(name), position);
+                spawn_creature(&mut commands, &asset_server, &archetypes, &creature);
+            }
+            ScriptCommand::SpawnNeutral { name, position } => {
+                let creature = Creature::neytral(CreatureName(name), position);
+                spawn_creature(&mut commands, &asset_server, &archetypes, &creature);
+            }
+            ScriptCommand::DamagePlayer { amount } => {
+                for (target, _) in player.iter() {
+                    damage_events.send(DamageEvent {
+                        target,
+                        source: None,
+                        amount,
+                        damage_type: DamageType::Physical,
+                        critical: false,
+                        sound_type: DamageSoundType::Punch,
+                    });
+                }
+            }
+            ScriptCommand::HealPlayer { amount } => {
+                for (target, _) in player.iter() {
+                    heal_events.send(HealEvent { target, amount });
+                }
+            }
+            ScriptCommand::DamageCreatures { name, amount } => {
+                let name = CreatureName(name);
+                for (target, kind) in creatures.iter() {
+                    if kind.0 == name {
+                        damage_events.send(DamageEvent {
+                            target,
+                            source: None,
+                            amount,
+                            damage_type: DamageType::Physical,
+                            critical: false,
+                            sound_type: DamageSoundType::Punch,
+                        });
+                    }
+                }
+            }
+            ScriptCommand::PlaySound(sound) => match sound.as_str() {
+                "punch" => sound_events.send(SoundEvent {
+                    sound_layer: SoundLayer::ForeGround,
+                    sound_type: SoundType::Damage(DamageSoundType::Punch),
+                    position: None,
+                    source: None,
+                }),
+                "bullet" => sound_events.send(SoundEvent {
+                    sound_layer: SoundLayer::ForeGround,
+                    sound_type: SoundType::Damage(DamageSoundType::Bullet),
+                    position: None,
+                    source: None,
+                }),
+                _ => warn!("Level script requested unknown sound {sound}"),
+            },
+            ScriptCommand::SwitchLevel(name) => switch_events.send(SwitchLevelEvent { name }),
+        }
+    }
+}
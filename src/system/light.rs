@@ -1,19 +1,22 @@
+use crate::level::LevelObjectMarker;
 use bevy::prelude::*;
 use lib::entity::level::Level;
 use lib::entity::voxel::Voxel;
 
 pub fn setup(mut commands: Commands, level: Res<Level>) {
-    if level.is_day() {
+    if level.is_changed() && level.is_day() {
         // TODO normal light
-        commands.spawn_bundle(PointLightBundle {
-            transform: Transform::from_xyz(5.0, 9.0, 5.0),
-            point_light: PointLight {
-                intensity: 5500.0,
-                range: 30.0,
+        commands
+            .spawn_bundle(PointLightBundle {
+                transform: Transform::from_xyz(5.0, 9.0, 5.0),
+                point_light: PointLight {
+                    intensity: 5500.0,
+                    range: 30.0,
+                    ..Default::default()
+                },
                 ..Default::default()
-            },
-            ..Default::default()
-        });
+            })
+            .insert(LevelObjectMarker);
     }
 }
 
@@ -49,9 +52,11 @@ fn spawn_point_light_source_inside(
     voxel: &Voxel,
 ) {
     let pos = &voxel.position;
-    commands.spawn_bundle(PointLightBundle {
-        transform: Transform::from_xyz(pos.x, pos.y, pos.z),
-        point_light,
-        ..Default::default()
-    });
+    commands
+        .spawn_bundle(PointLightBundle {
+            transform: Transform::from_xyz(pos.x, pos.y, pos.z),
+            point_light,
+            ..Default::default()
+        })
+        .insert(LevelObjectMarker);
 }
@@ -0,0 +1,40 @@
+use crate::creature::boss::Boss;
+use crate::creature::component::hp::HP;
+use crate::creature::component::perception::Perception;
+use bevy::prelude::*;
+use bevy_egui::egui::{Align2, ProgressBar, RichText};
+use bevy_egui::{egui, EguiContext};
+
+// Part of the window width the bar takes
+const BAR_WIDTH: f32 = 0.6;
+
+/// Shown once the boss notices a target or gets hit.
+pub fn ui_render_boss_hp(
+    mut egui_context: ResMut<EguiContext>,
+    windows: Res<Windows>,
+    bosses: Query<(&Boss, &HP, &Perception)>,
+) {
+    let boss = bosses
+        .iter()
+        .find(|(_, hp, perception)| perception.memory().is_some() || hp.percent() < 1.);
+    let (boss, hp) = if let Some((boss, hp, _)) = boss {
+        (boss, hp)
+    } else {
+        return;
+    };
+    let width = windows.get_primary().unwrap().width() * BAR_WIDTH;
+
+    egui::Area::new("Boss HP")
+        .anchor(Align2::CENTER_TOP, [0.0, 20.0])
+        .show(egui_context.ctx_mut(), |ui| {
+            ui.set_min_width(width);
+            ui.vertical_centered(|ui| {
+                ui.label(RichText::new(boss.title()).heading());
+                ui.add(
+                    ProgressBar::new(hp.percent())
+                        .desired_width(width)
+                        .text(format!("{} / {}", hp.current(), hp.max())),
+                );
+            });
+        });
+}
@@ -0,0 +1,39 @@
+use crate::creature::component::behaviour::Behaviour;
+use crate::creature::component::hp::HP;
+use crate::creature::component::perception::{Perception, Stimulus};
+use crate::creature::CreatureKind;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+use lib::util::debug_settings::DebugSettings;
+
+pub fn ui_render_creature_states(
+    mut egui_context: ResMut<EguiContext>,
+    settings: Res<DebugSettings>,
+    creatures: Query<(Entity, &CreatureKind, &Behaviour, &Perception, &HP)>,
+) {
+    if !settings.creature_states {
+        return;
+    }
+
+    egui::Window::new("Creatures")
+        .default_pos((20.0, 20.0))
+        .show(egui_context.ctx_mut(), |ui| {
+            egui::Grid::new("creature_states")
+                .striped(true)
+                .show(ui, |ui| {
+                    for (entity, kind, behaviour, perception, hp) in creatures.iter() {
+                        let memory = match perception.memory() {
+                            Some(memory) if memory.stimulus == Stimulus::Sight => "saw target",
+                            Some(_) => "heard noise",
+                            None => "",
+                        };
+                        ui.label(format!("{} {}", kind.0, entity.id()));
+                        ui.label(format!("{:?}", behaviour.state()));
+                        ui.label(format!("{:.1}s", behaviour.time_in_state().as_secs_f32()));
+                        ui.label(memory);
+                        ui.label(format!("{} / {}", hp.current(), hp.max()));
+                        ui.end_row();
+                    }
+                });
+        });
+}
@@ -1,4 +1,5 @@
 use crate::creature::component::hp::HP;
+use crate::player::attack::ranged::RangedWeapon;
 use crate::player::PlayerMarker;
 use bevy::prelude::*;
 use bevy_egui::egui::{vec2, Pos2, ProgressBar};
@@ -7,7 +8,7 @@ use bevy_egui::{egui, EguiContext};
 pub fn ui_render_hp(
     mut egui_context: ResMut<EguiContext>,
     windows: Res<Windows>,
-    player_query: Query<(&HP, With<PlayerMarker>)>,
+    player_query: Query<(&HP, Option<&RangedWeapon>), With<PlayerMarker>>,
 ) {
     let window = windows.get_primary().unwrap();
     let window_height = window.height();
@@ -17,18 +18,25 @@ pub fn ui_render_hp(
     egui::Area::new("HP")
         .fixed_pos(Pos2 {
             x: 20.0,
-            y: window_height - 40.0,
+            y: window_height - 60.0,
         })
         .show(context, |ui| {
             egui::Resize::default()
-                .fixed_size(vec2(250.0, 40.0))
+                .fixed_size(vec2(250.0, 60.0))
                 .show(ui, |ui| {
-                    if let Some((hp, ..)) = player {
+                    if let Some((hp, weapon)) = player {
                         ui.add(ProgressBar::new(hp.percent()).text(format!(
                             "{} / {}",
                             hp.current(),
                             hp.max()
                         )));
+                        if let Some(weapon) = weapon {
+                            if weapon.is_reloading() {
+                                ui.label("Reloading...");
+                            } else {
+                                ui.label(format!("Ammo {} / {}", weapon.ammo(), weapon.magazine));
+                            }
+                        }
                     }
                 });
         });
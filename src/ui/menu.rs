@@ -1,7 +1,11 @@
+use crate::creature::component::hp::HP;
+use crate::player::inventory::{use_item, Inventory};
+use crate::player::PlayerMarker;
 use crate::ui::{settings, MenuState};
 use bevy::prelude::*;
 use bevy_egui::egui::{vec2, Ui};
 use bevy_egui::{egui, EguiContext};
+use lib::entity::item::{ItemKind, ItemParams, Items};
 use lib::util::game_settings::GameSettings;
 use std::process::exit;
 
@@ -9,6 +13,8 @@ pub fn render(
     mut egui_context: ResMut<EguiContext>,
     game_settings: ResMut<GameSettings>,
     menu_state: ResMut<State<MenuState>>,
+    items: Res<Items>,
+    mut player: Query<(&mut Inventory, &mut HP), With<PlayerMarker>>,
 ) {
     egui::Window::new("Menu")
         .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
@@ -16,11 +22,17 @@ pub fn render(
         .collapsible(false)
         .show(egui_context.ctx_mut(), |ui| match menu_state.current() {
             MenuState::Main => render_main_menu(ui, menu_state),
+            MenuState::Inventory => render_inventory_menu(ui, menu_state, &items, &mut player),
             MenuState::GameSettings => render_game_settings_menu(ui, menu_state, game_settings),
         });
 }
 
 fn render_main_menu(ui: &mut Ui, mut menu_state: ResMut<State<MenuState>>) {
+    let inventory_button = ui.button("Inventory");
+    if inventory_button.clicked() {
+        menu_state.set(MenuState::Inventory).unwrap();
+    }
+
     let settings_button = ui.button("Settings");
     if settings_button.clicked() {
         menu_state.set(MenuState::GameSettings).unwrap();
@@ -34,6 +46,45 @@ fn render_main_menu(ui: &mut Ui, mut menu_state: ResMut<State<MenuState>>) {
     }
 }
 
+fn render_inventory_menu(
+    ui: &mut Ui,
+    mut menu_state: ResMut<State<MenuState>>,
+    items: &Items,
+    player: &mut Query<(&mut Inventory, &mut HP), With<PlayerMarker>>,
+) {
+    if let Ok((mut inventory, mut hp)) = player.get_single_mut() {
+        if inventory.stacks().is_empty() {
+            ui.label("Nothing here yet");
+        }
+
+        let mut used = None;
+        egui::Grid::new("inventory").show(ui, |ui| {
+            for (slot, stack) in inventory.stacks().iter().enumerate() {
+                let item = items.get(&stack.item);
+                ui.label(item.map_or(stack.item.as_str(), ItemParams::title));
+                ui.label(format!("x{}", stack.count));
+                let usable = item.map_or(false, |item| {
+                    matches!(item.kind, ItemKind::Consumable { .. })
+                });
+                if usable && ui.button("Use").clicked() {
+                    used = Some(slot);
+                }
+                ui.end_row();
+            }
+        });
+
+        // The game is paused, so the heal is applied right away instead of by an event
+        if let Some(amount) = used.and_then(|slot| use_item(&mut inventory, slot, items)) {
+            hp.heal(amount);
+        }
+    }
+
+    let back_button = ui.button("Back");
+    if back_button.clicked() {
+        menu_state.set(MenuState::Main).unwrap();
+    }
+}
+
 fn render_game_settings_menu(
     ui: &mut Ui,
     mut menu_state: ResMut<State<MenuState>>,
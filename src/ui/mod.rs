@@ -1,5 +1,8 @@
+mod boss;
+mod creature_states;
 mod hp;
 pub mod menu;
+mod score;
 mod settings;
 
 use crate::GameState;
@@ -13,6 +16,7 @@ pub struct UIPlugin;
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
 pub enum MenuState {
     Main,
+    Inventory,
     GameSettings,
 }
 
@@ -22,6 +26,9 @@ impl Plugin for UIPlugin {
         app.add_state(MenuState::Main)
             .add_startup_system(ui_setup_theme)
             .add_system(hp::ui_render_hp)
+            .add_system(boss::ui_render_boss_hp)
+            .add_system(score::ui_render_score)
+            .add_system(creature_states::ui_render_creature_states)
             .add_system(ui_track_menu_keyboard_interaction)
             .add_system_set(
                 SystemSet::on_update(GameState::Pause)
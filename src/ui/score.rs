@@ -0,0 +1,33 @@
+use crate::creature::death::KillStats;
+use crate::creature::spawner::arena::{Arena, ArenaState};
+use bevy::prelude::*;
+use bevy_egui::egui::{Align2, RichText};
+use bevy_egui::{egui, EguiContext};
+
+pub fn ui_render_score(
+    mut egui_context: ResMut<EguiContext>,
+    stats: Res<KillStats>,
+    arena: Option<Res<Arena>>,
+) {
+    egui::Area::new("Score")
+        .anchor(Align2::RIGHT_TOP, [-20.0, 20.0])
+        .show(egui_context.ctx_mut(), |ui| {
+            ui.label(RichText::new(format!("Score: {}", stats.score)).heading());
+            ui.label(format!("Kills: {}", stats.total_kills()));
+
+            if let Some(arena) = arena {
+                match arena.state() {
+                    ArenaState::Waiting => {}
+                    ArenaState::Fighting => {
+                        ui.label(format!("Wave {}: {} left", arena.wave(), arena.alive()));
+                    }
+                    ArenaState::Break(_) => {
+                        ui.label(format!("Wave {} cleared", arena.wave()));
+                    }
+                    ArenaState::Finished => {
+                        ui.label("Arena cleared");
+                    }
+                }
+            }
+        });
+}